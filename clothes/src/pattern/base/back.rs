@@ -1,4 +1,5 @@
 use pmdraw::shapes::{bezier::Bezier, line::Line, point::Point};
+use pmmath::transform::Transform2;
 
 use crate::pattern::{common::dart::Dart, measurements::Cm};
 
@@ -48,8 +49,7 @@ impl Back {
     }
 
     pub fn rotate(&mut self, angle_degree: f64, around: Point) {
-        self.for_each_bezier_mut(|b| b.rotate(angle_degree, around));
-        self.for_each_line_mut(|l| l.rotate(angle_degree, around));
+        self.transform(&Transform2::rotate_around(angle_degree, around.x, around.y));
     }
 
     pub fn rotate_around_center(&mut self, angle_degree: f64) {
@@ -57,12 +57,16 @@ impl Back {
             .waist
             .midddle()
             .to(0.0, self.waist.end.middle(self.side.end).y);
-        self.for_each_bezier_mut(|b| b.rotate(angle_degree, center));
-        self.for_each_line_mut(|l| l.rotate(angle_degree, center));
+        self.rotate(angle_degree, center);
     }
 
     pub fn to(&mut self, dx: Cm, dy: Cm) {
         self.for_each_bezier_mut(|b| b.to(dx, dy));
         self.for_each_line_mut(|l| l.to(dx, dy));
     }
+
+    pub fn transform(&mut self, m: &Transform2) {
+        self.for_each_bezier_mut(|b| b.transform(m));
+        self.for_each_line_mut(|l| l.transform(m));
+    }
 }
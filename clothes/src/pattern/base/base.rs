@@ -1,7 +1,10 @@
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
-use pmdraw::shapes::{bezier::Bezier, line::Line, point::Point};
+use pmdraw::{
+    construction::circle_line,
+    shapes::{bezier::Bezier, circle::Circle, line::Line, point::Point},
+};
 
 use crate::pattern::{
     base::front::Front,
@@ -112,14 +115,22 @@ impl Base {
             .to(0.7, 0.0);
         let arm_hole_radius = (center_back.end - chest.end).norm() / 2.0;
         let chest_dart_fst = arm_hole_left_bottom.to(0.0, arm_hole_radius / 3.0 * 2.0);
-        let chest_dart_bottom = Line::new(chest_dart_middle, chest_dart_fst);
-        let chest_dart_snd = Point::new(
-            chest_dart_fst.x - 0.7,
-            chest_dart_middle.y
-                + (chest_dart_bottom.len().powf(2.0)
-                    - (arm_hole_left_bottom.x - 0.7 - chest_dart_middle.x).powf(2.0))
-                .sqrt(),
+        // the second edge of chest dart is as long as the first one and 0.7 cm left of it
+        let snd_vertical = Line::new(
+            Point::new(chest_dart_fst.x - 0.7, chest_dart_middle.y),
+            Point::new(chest_dart_fst.x - 0.7, chest_dart_fst.y),
         );
+        let chest_dart_snd = circle_line(
+            &Circle::new(
+                chest_dart_middle,
+                chest_dart_middle.distance(chest_dart_fst),
+            ),
+            &snd_vertical,
+        )
+        .expect("Chest dart is shorter than its width")
+        .into_iter()
+        .max_by(|p, q| p.y.partial_cmp(&q.y).unwrap())
+        .unwrap();
         let chest_dart = Dart::new(chest_dart_fst, chest_dart_middle, chest_dart_snd);
 
         let back_neck_end = center_back
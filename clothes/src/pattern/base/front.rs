@@ -1,4 +1,5 @@
 use pmdraw::shapes::{bezier::Bezier, line::Line};
+use pmmath::transform::Transform2;
 
 use crate::pattern::{common::dart::Dart, measurements::Cm};
 
@@ -49,4 +50,9 @@ impl Front {
         self.for_each_bezier_mut(|b| b.to(dx, dy));
         self.for_each_line_mut(|l| l.to(dx, dy));
     }
+
+    pub fn transform(&mut self, m: &Transform2) {
+        self.for_each_bezier_mut(|b| b.transform(m));
+        self.for_each_line_mut(|l| l.transform(m));
+    }
 }
@@ -1,16 +1,37 @@
 use pmdraw::{
+    contour::{Contour, Segment},
     drawing::Drawing,
-    shapes::{bezier::Bezier, line::Line},
+    shapes::{bezier::Bezier, line::Line, Shape},
 };
+use pmmath::transform::Transform2;
 
 use crate::pattern::measurements::Cm;
 
+/// error of margins along curves allowed
+const MARGIN_TOLERANCE: Cm = 0.01;
+
 #[derive(Clone)]
 enum Path {
     Line(Line),
     Curve(Bezier),
 }
 
+impl Path {
+    fn to_shape(&self) -> Shape {
+        match self {
+            Path::Line(l) => Shape::Line(*l),
+            Path::Curve(c) => Shape::Bezier(c.clone()),
+        }
+    }
+
+    fn transform(&mut self, m: &Transform2) {
+        match self {
+            Path::Line(l) => l.transform(m),
+            Path::Curve(c) => c.transform(m),
+        }
+    }
+}
+
 #[derive(Clone)]
 struct Margin {
     path: Path,
@@ -32,18 +53,23 @@ impl Pattern {
         self.paths.push(Path::Line(l))
     }
 
+    /// Outline of the pattern without margins
+    pub fn contour(&self) -> Contour {
+        Contour::new(
+            self.paths
+                .iter()
+                .map(|path| Segment::new(path.to_shape()))
+                .collect(),
+        )
+    }
+
     pub fn draw(&self, drawing: &mut Drawing) {
-        for path in &self.paths {
-            match &path {
-                Path::Curve(c) => drawing.bezier(c),
-                Path::Line(l) => drawing.line_borrow(l),
-            };
-        }
-        for path in &self.margin {
-            match &path.path {
-                Path::Curve(c) => drawing.bezier(c),
-                Path::Line(l) => drawing.line_borrow(l),
-            };
+        for path in self
+            .paths
+            .iter()
+            .chain(self.margin.iter().map(|margin| &margin.path))
+        {
+            drawing.shape(path.to_shape());
         }
     }
 
@@ -65,16 +91,23 @@ impl Pattern {
             margin.len(),
             "margins must be specified for all the paths"
         );
-        // add outer lines and curves
+        // add outer lines and curves, where pieces of an offset curve are already joined
         for i in 0..self.paths.len() {
-            let path = &self.paths[i];
-            self.margin.push(Margin {
-                path: match path {
-                    Path::Curve(c) => Path::Curve(c.parallel(margin[i]).right),
-                    Path::Line(l) => Path::Line(l.parallel(margin[i]).right),
-                },
-                skips_join: except.contains(&i),
-            })
+            let paths = match &self.paths[i] {
+                Path::Curve(c) => c
+                    .offset(margin[i], MARGIN_TOLERANCE)
+                    .into_iter()
+                    .map(Path::Curve)
+                    .collect(),
+                Path::Line(l) => vec![Path::Line(l.parallel(margin[i]).right)],
+            };
+            let count_paths = paths.len();
+            for (j, path) in paths.into_iter().enumerate() {
+                self.margin.push(Margin {
+                    path,
+                    skips_join: j < count_paths - 1 || except.contains(&i),
+                })
+            }
         }
         // join them
         let mut i = 0;
@@ -130,6 +163,7 @@ impl Pattern {
             // update paths with joined ones
             self.margin[i] = path.clone();
             self.margin[next_i] = next_path.clone();
+            let count_margins = self.margin.len();
             match bridge_between_paths_to_concatenate_them {
                 (Some(l), None) => {
                     self.margin.insert(
@@ -158,6 +192,11 @@ impl Pattern {
                 }
                 _ => (),
             };
+            // bridges inserted in front of the last path shift its index,
+            // skip them not to join the last path again
+            if next_i == 0 {
+                i += self.margin.len() - count_margins;
+            }
             i += 1;
         }
     }
@@ -170,17 +209,42 @@ impl Pattern {
     }
 
     pub fn to(&mut self, dx: Cm, dy: Cm) -> () {
-        for path in &mut self.paths {
-            match path {
-                Path::Curve(c) => c.to(dx, dy),
-                Path::Line(l) => l.to(dx, dy),
-            };
-        }
-        for path in &mut self.margin {
-            match &mut path.path {
-                Path::Curve(c) => c.to(dx, dy),
-                Path::Line(l) => l.to(dx, dy),
-            };
+        self.transform(&Transform2::translate(dx, dy))
+    }
+
+    /// Transform paths and margins by an affine transformation
+    pub fn transform(&mut self, m: &Transform2) {
+        for path in self
+            .paths
+            .iter_mut()
+            .chain(self.margin.iter_mut().map(|margin| &mut margin.path))
+        {
+            path.transform(m);
         }
     }
 }
+
+#[cfg(test)]
+mod test_pattern {
+    use pmdraw::shapes::{bezier::Bezier, line::Line, point::Point};
+
+    use crate::pattern::common::pattern::{Pattern, MARGIN_TOLERANCE};
+
+    #[test]
+    fn test_generate_margin() {
+        let curve = Bezier::new_with_ctrl_points(vec![
+            Point::new(10.0, 10.0),
+            Point::new(0.0, 12.0),
+            Point::new(0.0, 0.0),
+        ]);
+        let mut pattern = Pattern::new();
+        pattern.add_line(Line::new(Point::new(0.0, 0.0), Point::new(10.0, 0.0)));
+        pattern.add_line(Line::new(Point::new(10.0, 0.0), Point::new(10.0, 10.0)));
+        pattern.add_curve(curve.clone());
+        pattern.generate_margin(-1.0);
+        // two lines, pieces of the offset curve, and a bridge on each side of the curve,
+        // where the bridge between the last path and the first one is inserted only once
+        let count_pieces = curve.offset(-1.0, MARGIN_TOLERANCE).len();
+        assert_eq!(pattern.margin.len(), 2 + count_pieces + 2);
+    }
+}
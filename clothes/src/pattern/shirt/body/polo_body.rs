@@ -24,7 +24,7 @@ impl PoloBody {
         let placket_width = 3.4;
         let waist = waist.origin.line_to(waist.point_from_end(1.0));
         let bottom = waist.parallel(measurements.waist_to_hip).right;
-        let arm_hole_origin = f_base.arm_hole.0.range.from.to(0.0, -3.0);
+        let arm_hole_origin = f_base.arm_hole.0.range().from.to(0.0, -3.0);
         let side = Bezier::new(vec![bottom.end, waist.end, arm_hole_origin]);
         let arm_hole_end = f_base.shoulder.parallel(1.0).right.point_from_origin(-2.5);
         let front_arm_hole = Bezier::new(vec![
@@ -46,7 +46,7 @@ impl PoloBody {
             Some(shoulder.end),
             Some(neck_end),
         );
-        let placket_end = f_base.arm_hole.0.range.from.to(-waist.len() - 1.0, 0.0);
+        let placket_end = f_base.arm_hole.0.range().from.to(-waist.len() - 1.0, 0.0);
         let placket_end_mark = placket_end.line_to(placket_end.to(1.0, 0.0));
         let placket_cut_mark = placket_end_mark.parallel(3.0).left;
         let placket_length = (center.origin - placket_end).y;
@@ -111,7 +111,7 @@ impl PoloBody {
             b_base.shoulder_dart.middle(),
         );
         shoulder.extend_end(2.5);
-        let arm_hole_end = b_base.arm_hole.range.to.to(0.0, -3.0);
+        let arm_hole_end = b_base.arm_hole.range().to.to(0.0, -3.0);
         let mut back_arm_hole = b_base.arm_hole.clone();
         let arm_hole_end_index = back_arm_hole.get_range_index().1;
         back_arm_hole.refit(
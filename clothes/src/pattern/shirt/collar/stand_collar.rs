@@ -29,24 +29,23 @@ impl StandCollar {
         top_ctrl_line_end.rotate(90.0, bottom_origin);
         let top_ctrl_line = top_origin.to(-6.0, 0.0).line_to(top_ctrl_line_end);
         let top_3rd_point = top_ctrl_line.parallel(0.2).right.midddle();
-        let top_end = top_ctrl_line.intersection(
-            &bottom_origin
-                .line_to(top_ctrl_line_end)
-                .parallel(placket_width)
-                .right,
-        );
-        let top = Bezier::new_with_t(
-            &vec![
-                bottom_origin.mirror(mirror_line),
-                top_end.mirror(mirror_line),
-                top_3rd_point.mirror(mirror_line),
-                top_origin,
-                top_3rd_point,
-                top_end,
-                bottom_origin,
-            ],
-            &vec![0.0, 0.16, 0.32, 0.5, 0.68, 0.84, 1.0],
-        );
+        let top_end = top_ctrl_line
+            .intersection(
+                &bottom_origin
+                    .line_to(top_ctrl_line_end)
+                    .parallel(placket_width)
+                    .right,
+            )
+            .expect("Top of the collar must cross the placket");
+        let top = Bezier::new(vec![
+            bottom_origin.mirror(mirror_line),
+            top_end.mirror(mirror_line),
+            top_3rd_point.mirror(mirror_line),
+            top_origin,
+            top_3rd_point,
+            top_end,
+            bottom_origin,
+        ]);
         pattern.add_curve(bottom);
         pattern.add_curve(top);
         pattern.generate_margin(0.5);
@@ -1,4 +1,7 @@
-use pmdraw::shapes::{bezier::Bezier, point::Point};
+use pmdraw::{
+    construction::circle_line,
+    shapes::{bezier::Bezier, circle::Circle, line::Line, point::Point},
+};
 
 use crate::pattern::{common::pattern::Pattern, measurements::Cm};
 
@@ -17,8 +20,23 @@ impl ShortSleeve {
         let top_left = Point::new(0.0, 0.0);
         let arm_hole = front_arm_hole + back_arm_hole;
         let top_height = arm_hole / 6.0;
-        let left_width = ((front_arm_hole - 0.5).powf(2.0) - top_height.powf(2.0)).sqrt();
-        let right_width = ((back_arm_hole + 0.5).powf(2.0) - top_height.powf(2.0)).sqrt();
+        // arm holes reach from the center at the top height to the edges of the width line below it
+        let arm_hole_center = Point::new(0.0, top_height);
+        let width_line = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        let left_width = -circle_line(
+            &Circle::new(arm_hole_center, front_arm_hole - 0.5),
+            &width_line,
+        )
+        .expect("Front arm hole is shorter than the sleeve top")[0]
+            .x;
+        let right_width = circle_line(
+            &Circle::new(arm_hole_center, back_arm_hole + 0.5),
+            &width_line,
+        )
+        .expect("Back arm hole is shorter than the sleeve top")
+        .last()
+        .unwrap()
+        .x;
         let top_right = top_left.to(left_width + right_width, 0.0);
         let top_middle = top_left.middle(top_right).to(0.0, top_height);
         let top_left_middle = top_left.middle(top_middle).to_point(top_left, 2.5);
@@ -32,17 +50,14 @@ impl ShortSleeve {
             .parallel(1.0)
             .right
             .between(0.25);
-        let top = Bezier::new_with_t(
-            &vec![
-                top_right,
-                top_right_middle,
-                top_middle,
-                top_left_middle1,
-                top_left_middle,
-                top_left,
-            ],
-            &vec![0.0, 0.24, 0.38, 0.52, 0.76, 1.0],
-        );
+        let top = Bezier::new(vec![
+            top_right,
+            top_right_middle,
+            top_middle,
+            top_left_middle1,
+            top_left_middle,
+            top_left,
+        ]);
         let width_offset = (left_width + right_width) / 8.0 - 1.5;
         let bottom_origin = top_left.to(width_offset, -(sleeve_len - top_height));
         let bottom_end = top_right.to(-width_offset, -(sleeve_len - top_height));
@@ -0,0 +1,359 @@
+use crate::{
+    contour::{Contour, Orientation, Segment},
+    shapes::{bezier::TOLERANCE, geometry::Geometry, intersection::Intersections, point::Point},
+};
+
+/// How two contours are combined
+#[derive(Copy, Clone, PartialEq)]
+pub enum Operation {
+    /// inside either contour
+    Union,
+    /// inside both contours
+    Intersection,
+    /// inside the first contour but outside the second
+    Difference,
+    /// inside just one of the contours
+    Xor,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BooleanError {
+    /// pieces do not make closed contours even after both contours are flattened,
+    /// e.g. a contour is not closed or crosses itself
+    NotClosed,
+}
+
+/// Where a piece of a contour is relative to the other contour
+#[derive(Copy, Clone, PartialEq)]
+enum Location {
+    Inside,
+    Outside,
+    /// on a piece of the other contour, which goes in the same direction or not
+    Shared {
+        is_same_direction: bool,
+    },
+}
+
+/// Combine the regions of two closed contours
+///
+/// segments are cut where the contours meet, and the pieces on the boundary of the result are chained again,
+/// thus lines, curves, and arcs are kept as they are. if the pieces fail to make closed contours,
+/// e.g. curves touch each other too closely to find where they meet, both contours are flattened and combined again,
+/// which is an error if the pieces still fail.
+/// the result is the outer contours going counterclockwise and holes in them going clockwise
+pub fn boolean(
+    a: &Contour,
+    b: &Contour,
+    operation: Operation,
+) -> Result<Vec<Contour>, BooleanError> {
+    if operation == Operation::Xor {
+        let mut contours = boolean(a, b, Operation::Difference)?;
+        contours.extend(boolean(b, a, Operation::Difference)?);
+        return Ok(contours);
+    }
+    let (a, b) = (counterclockwise(a), counterclockwise(b));
+    let contours = combine(&a, &b, operation);
+    if contours.iter().all(|c| c.is_closed()) {
+        return Ok(contours);
+    }
+    let contours = combine(&a.flatten(TOLERANCE), &b.flatten(TOLERANCE), operation);
+    if contours.iter().all(|c| c.is_closed()) {
+        Ok(contours)
+    } else {
+        Err(BooleanError::NotClosed)
+    }
+}
+
+/// Combine contours going counterclockwise by the location of each piece
+fn combine(a: &Contour, b: &Contour, operation: Operation) -> Vec<Contour> {
+    let (pieces_a, pieces_b) = cut_where_they_meet(a, b);
+    let mut kept = Vec::new();
+    for piece in &pieces_a {
+        let is_kept = match (operation, locate(piece, b, &pieces_b)) {
+            (Operation::Union, Location::Outside)
+            | (Operation::Intersection, Location::Inside)
+            | (Operation::Difference, Location::Outside) => true,
+            // a boundary shared by both contours is kept once, from the first contour
+            (Operation::Union, Location::Shared { is_same_direction })
+            | (Operation::Intersection, Location::Shared { is_same_direction }) => {
+                is_same_direction
+            }
+            (Operation::Difference, Location::Shared { is_same_direction }) => !is_same_direction,
+            _ => false,
+        };
+        if is_kept {
+            kept.push(piece.clone());
+        }
+    }
+    for piece in &pieces_b {
+        match (operation, locate(piece, a, &pieces_a)) {
+            (Operation::Union, Location::Outside) | (Operation::Intersection, Location::Inside) => {
+                kept.push(piece.clone())
+            }
+            // the second contour bounds the difference from the inside of the first one
+            (Operation::Difference, Location::Inside) => {
+                let mut piece = piece.clone();
+                piece.reverse();
+                kept.push(piece);
+            }
+            _ => (),
+        }
+    }
+    chain(kept)
+}
+
+/// Pieces of segments of both contours, where segments are cut at points where they meet the other contour
+///
+/// where segments overlap, they are cut at the edges of the other segment
+fn cut_where_they_meet(a: &Contour, b: &Contour) -> (Vec<Segment>, Vec<Segment>) {
+    let mut cuts_a = vec![Vec::new(); a.segments().len()];
+    let mut cuts_b = vec![Vec::new(); b.segments().len()];
+    for (i, segment_a) in a.segments().iter().enumerate() {
+        for (j, segment_b) in b.segments().iter().enumerate() {
+            match segment_a.shape().intersect(segment_b.shape()) {
+                Intersections::Points(points) => {
+                    for each in points {
+                        cuts_a[i].push(each.t1);
+                        cuts_b[j].push(each.t2);
+                    }
+                }
+                Intersections::Overlapping { t1, t2 } => {
+                    cuts_a[i].extend(vec![t1.from, t1.to]);
+                    cuts_b[j].extend(vec![t2.from, t2.to]);
+                }
+                Intersections::Coincident => {
+                    for p in [segment_b.start(), segment_b.end()].iter() {
+                        let projection = segment_a.shape().project(*p);
+                        if projection.distance <= TOLERANCE {
+                            cuts_a[i].push(projection.t);
+                        }
+                    }
+                    for p in [segment_a.start(), segment_a.end()].iter() {
+                        let projection = segment_b.shape().project(*p);
+                        if projection.distance <= TOLERANCE {
+                            cuts_b[j].push(projection.t);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let cut_all = |c: &Contour, cuts: Vec<Vec<f64>>| {
+        c.segments()
+            .iter()
+            .zip(cuts)
+            .flat_map(|(segment, cuts)| cut(segment, cuts))
+            .collect::<Vec<Segment>>()
+    };
+    (cut_all(a, cuts_a), cut_all(b, cuts_b))
+}
+
+/// Pieces of the segment cut at each t, where pieces shorter than the tolerance are merged into the next one
+fn cut(segment: &Segment, cuts: Vec<f64>) -> Vec<Segment> {
+    let (from, to) = segment.t_range();
+    // how far each cut is from the start, which is 0 at the start and 1 at the end
+    let mut fractions = cuts
+        .into_iter()
+        .map(|t| (t - from) / (to - from))
+        .filter(|fraction| 0.0 < *fraction && *fraction < 1.0)
+        .collect::<Vec<f64>>();
+    fractions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let t_at = |fraction: f64| from + (to - from) * fraction;
+    let mut edges = vec![0.0];
+    for fraction in fractions.into_iter().chain(std::iter::once(1.0)) {
+        let previous = segment.point_at(t_at(edges[edges.len() - 1]));
+        if segment.point_at(t_at(fraction)).distance(previous) > TOLERANCE {
+            edges.push(fraction);
+        } else if fraction == 1.0 {
+            // the last piece is too short, which is merged into the previous one
+            let last = edges.len() - 1;
+            edges[last] = 1.0;
+        }
+    }
+    if edges.len() == 1 {
+        return vec![segment.clone()];
+    }
+    edges
+        .windows(2)
+        .map(|edge| segment.sub_segment(t_at(edge[0]), t_at(edge[1])))
+        .collect()
+}
+
+/// Location of a piece relative to the other contour, which is cut into pieces where they meet
+fn locate(piece: &Segment, other: &Contour, other_pieces: &[Segment]) -> Location {
+    let (from, to) = piece.t_range();
+    let middle = piece.point_at((from + to) / 2.0);
+    let (start, end) = (piece.start(), piece.end());
+    let is_near = |p: Point, q: Point| p.distance(q) <= TOLERANCE;
+    let shared = other_pieces.iter().find(|other_piece| {
+        let (other_start, other_end) = (other_piece.start(), other_piece.end());
+        ((is_near(start, other_start) && is_near(end, other_end))
+            || (is_near(start, other_end) && is_near(end, other_start)))
+            && other_piece.shape().project(middle).distance <= TOLERANCE
+    });
+    match shared {
+        Some(other_piece) => Location::Shared {
+            is_same_direction: is_near(start, other_piece.start()),
+        },
+        None if other.contains(middle) => Location::Inside,
+        None => Location::Outside,
+    }
+}
+
+/// Closed contours made by chaining pieces, where each piece is followed by the one starting at its end
+fn chain(pieces: Vec<Segment>) -> Vec<Contour> {
+    let mut unused = pieces
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<Option<Segment>>>();
+    let mut contours = Vec::new();
+    while let Some(first) = unused.iter_mut().find_map(|piece| piece.take()) {
+        let start = first.start();
+        let mut segments = vec![first];
+        loop {
+            let end = segments[segments.len() - 1].end();
+            if end.distance(start) <= TOLERANCE {
+                break;
+            }
+            // the nearest start to the end
+            let next = unused
+                .iter()
+                .enumerate()
+                .filter_map(|(i, piece)| {
+                    piece.as_ref().map(|piece| (i, piece.start().distance(end)))
+                })
+                .filter(|(_, distance)| *distance <= TOLERANCE)
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+            match next {
+                Some((i, _)) => segments.push(unused[i].take().unwrap()),
+                None => break,
+            }
+        }
+        contours.push(Contour::new(segments));
+    }
+    contours
+}
+
+fn counterclockwise(c: &Contour) -> Contour {
+    let mut c = c.clone();
+    if c.orientation() == Orientation::Clockwise {
+        c.reverse();
+    }
+    c
+}
+
+#[cfg(test)]
+mod test_boolean {
+    use std::f64::consts::PI;
+
+    use crate::{
+        boolean::BooleanError,
+        contour::{Contour, Segment},
+        shapes::{arc::Arc, line::Line, point::Point, Shape},
+    };
+
+    fn rectangle(x0: f64, y0: f64, x1: f64, y1: f64) -> Contour {
+        let corners = [
+            Point::new(x0, y0),
+            Point::new(x1, y0),
+            Point::new(x1, y1),
+            Point::new(x0, y1),
+        ];
+        Contour::new(
+            (0..4)
+                .map(|i| Segment::new(Shape::Line(Line::new(corners[i], corners[(i + 1) % 4]))))
+                .collect(),
+        )
+    }
+
+    fn circle(x: f64, y: f64, r: f64) -> Contour {
+        Contour::new(vec![Segment::new(Shape::Arc(Arc::new(
+            Point::new(x, y),
+            r,
+            0.0,
+            360.0,
+        )))])
+    }
+
+    fn area(contours: &[Contour]) -> f64 {
+        assert!(contours.iter().all(|c| c.is_closed()));
+        contours.iter().map(|c| c.area()).sum()
+    }
+
+    #[test]
+    fn test_boolean_rectangles() {
+        let (a, b) = (
+            rectangle(0.0, 0.0, 10.0, 10.0),
+            rectangle(5.0, 5.0, 15.0, 15.0),
+        );
+        let union = a.union(&b).unwrap();
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 175.0).abs() < 1e-9);
+        assert!((area(&a.intersection(&b).unwrap()) - 25.0).abs() < 1e-9);
+        assert!((area(&a.difference(&b).unwrap()) - 75.0).abs() < 1e-9);
+        let xor = a.xor(&b).unwrap();
+        assert_eq!(xor.len(), 2);
+        assert!((area(&xor) - 150.0).abs() < 1e-9);
+        // a hole goes clockwise
+        let difference = a.difference(&rectangle(3.0, 3.0, 6.0, 6.0)).unwrap();
+        assert_eq!(difference.len(), 2);
+        assert!((area(&difference) - 91.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_boolean_circles() {
+        let (r, d) = (3.0, 4.0);
+        let (a, b) = (circle(0.0, 0.0, r), circle(d, 0.0, r));
+        let lens = 2.0 * r * r * (d / (2.0 * r)).acos() - d / 2.0 * (4.0 * r * r - d * d).sqrt();
+        let intersection = a.intersection(&b).unwrap();
+        assert_eq!(intersection.len(), 1);
+        assert!((area(&intersection) - lens).abs() < 1e-9);
+        assert!((area(&a.union(&b).unwrap()) - (2.0 * PI * r * r - lens)).abs() < 1e-9);
+        assert!((area(&a.difference(&b).unwrap()) - (PI * r * r - lens)).abs() < 1e-9);
+        // a circle over the edge of a rectangle
+        let rectangle = rectangle(0.0, -5.0, 10.0, 5.0);
+        let difference = rectangle.difference(&circle(10.0, 0.0, r)).unwrap();
+        assert!((area(&difference) - (100.0 - PI * r * r / 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_boolean_touching() {
+        let a = rectangle(0.0, 0.0, 10.0, 10.0);
+        // sharing an edge
+        let b = rectangle(10.0, 0.0, 12.0, 10.0);
+        let union = a.union(&b).unwrap();
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 120.0).abs() < 1e-9);
+        assert!(a.intersection(&b).unwrap().is_empty());
+        assert!((area(&a.difference(&b).unwrap()) - 100.0).abs() < 1e-9);
+        // sharing a part of an edge
+        let c = rectangle(10.0, 2.0, 12.0, 4.0);
+        let union = a.union(&c).unwrap();
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 104.0).abs() < 1e-9);
+        // coincident
+        let union = a.union(&a.clone()).unwrap();
+        assert_eq!(union.len(), 1);
+        assert!((area(&union) - 100.0).abs() < 1e-9);
+        assert!((area(&a.intersection(&a.clone()).unwrap()) - 100.0).abs() < 1e-9);
+        assert!(a.difference(&a.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_boolean_not_closed() {
+        let open = Contour::new(vec![
+            Segment::new(Shape::Line(Line::new(
+                Point::new(2.0, -1.0),
+                Point::new(2.0, 5.0),
+            ))),
+            Segment::new(Shape::Line(Line::new(
+                Point::new(2.0, 5.0),
+                Point::new(20.0, 5.0),
+            ))),
+        ]);
+        assert_eq!(
+            rectangle(0.0, 0.0, 10.0, 10.0).union(&open).err(),
+            Some(BooleanError::NotClosed)
+        );
+    }
+}
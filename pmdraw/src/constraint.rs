@@ -0,0 +1,426 @@
+use pmmath::{
+    binomial::binomial_row,
+    optimize::{forward_differences, levenberg_marquardt, MinimizeError},
+    quadrature::gauss_legendre,
+};
+
+use crate::shapes::{bezier::Bezier, line::Line, point::Point};
+
+/// error of residuals allowed by default, i.e. centimeters for distances and radians for angles
+pub const TOLERANCE: f64 = 1e-6;
+/// give up solving after this number of iterations
+const MAX_ITERATIONS: usize = 100;
+/// singular values of the jacobian smaller than this ratio to the largest one are regarded as 0
+const RANK_TOLERANCE: f64 = 1e-6;
+/// curve length is integrated over this number of intervals of t
+const LENGTH_INTERVALS: usize = 16;
+/// initial t of a point on a curve is the nearest one of this number of samples
+const SAMPLES: usize = 32;
+
+/// Point variable of a sketch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointId(usize);
+
+/// Line variable of a sketch, which is defined by two point variables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineId(usize);
+
+/// Bezier curve variable of a sketch, which is defined by its origin, end, and control point variables
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CurveId(usize);
+
+/// Edge of a curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveEnd {
+    Origin,
+    End,
+}
+
+/// Geometric relation which a sketch must satisfy
+#[derive(Clone)]
+pub enum Constraint {
+    /// angle in degrees from the first line to the second one, counter clockwise
+    Angle(LineId, LineId, f64),
+    /// two points are at the same position
+    Coincident(PointId, PointId),
+    /// length of the curve
+    CurveLength(CurveId, f64),
+    /// distance between two points
+    Distance(PointId, PointId, f64),
+    /// point is at the position
+    Fixed(PointId, Point),
+    Horizontal(LineId),
+    Parallel(LineId, LineId),
+    Perpendicular(LineId, LineId),
+    /// point is on the curve, including its extension beyond the origin and the end
+    PointOnCurve(PointId, CurveId),
+    /// curve is tangent to the line at the edge
+    Tangent(CurveId, CurveEnd, LineId),
+    Vertical(LineId),
+}
+
+/// Result of solving constraints
+#[derive(Debug, Clone)]
+pub struct Solution {
+    /// ||residuals|| of all constraints
+    pub residual_norm: f64,
+    /// number of independent ways to move points without breaking constraints.
+    /// if it is not 0, the sketch is under-constrained and a solution near the initial positions is chosen
+    pub degrees_of_freedom: usize,
+    /// number of equations implied by other constraints.
+    /// if it is not 0, the sketch is over-constrained, though all constraints are satisfied
+    pub redundant_equations: usize,
+}
+
+/// Reason why constraints are not solved
+#[derive(Debug, Clone)]
+pub enum ConstraintError {
+    /// constraints conflict with each other, hence they cannot be satisfied at the same time
+    /// - residual_norm - ||residuals|| of the best configuration found
+    /// - redundant_equations - number of equations implied by or conflicting with other constraints
+    Inconsistent {
+        residual_norm: f64,
+        redundant_equations: usize,
+    },
+    /// tolerance is not reached within max iterations
+    NotConverged { residual_norm: f64 },
+    /// constraints cannot be evaluated at the initial positions, such as tangent to a line of length 0
+    NotFinite,
+}
+
+/// Points, lines, and curves which are moved to satisfy constraints
+#[derive(Clone, Default)]
+pub struct Sketch {
+    points: Vec<Point>,
+    lines: Vec<(PointId, PointId)>,
+    curves: Vec<Vec<PointId>>,
+    constraints: Vec<Constraint>,
+}
+
+impl Sketch {
+    /// Add a curve of points as its origin, control points, and end
+    pub fn add_curve(&mut self, points: Vec<PointId>) -> CurveId {
+        assert!(points.len() >= 2, "Curve needs two points at least");
+        self.curves.push(points);
+        CurveId(self.curves.len() - 1)
+    }
+
+    pub fn add_line(&mut self, origin: PointId, end: PointId) -> LineId {
+        self.lines.push((origin, end));
+        LineId(self.lines.len() - 1)
+    }
+
+    /// Add a point variable whose initial position is p
+    ///
+    /// if the sketch has multiple solutions, the one near the initial positions is chosen
+    pub fn add_point(&mut self, p: Point) -> PointId {
+        self.points.push(p);
+        PointId(self.points.len() - 1)
+    }
+
+    pub fn constrain(&mut self, constraint: Constraint) {
+        self.constraints.push(constraint)
+    }
+
+    pub fn curve(&self, id: CurveId) -> Bezier {
+        Bezier::new_with_ctrl_points(self.curve_points(&self.points, id))
+    }
+
+    pub fn line(&self, id: LineId) -> Line {
+        let (origin, end) = self.lines[id.0];
+        Line::new(self.points[origin.0], self.points[end.0])
+    }
+
+    pub fn new() -> Sketch {
+        Sketch {
+            points: Vec::new(),
+            lines: Vec::new(),
+            curves: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    pub fn point(&self, id: PointId) -> Point {
+        self.points[id.0]
+    }
+
+    /// Move points to satisfy all constraints
+    ///
+    /// constraints are solved as a nonlinear least squares problem of x and y of points,
+    /// and the rank of its jacobian tells whether the sketch is under- or over-constrained
+    /// - tolerance - error of residuals allowed, i.e. centimeters for distances and radians for angles
+    pub fn solve(&mut self, tolerance: f64) -> Result<Solution, ConstraintError> {
+        let count_point_variables = 2 * self.points.len();
+        // a point on a curve needs t of the curve as a variable
+        let mut initial = self
+            .points
+            .iter()
+            .flat_map(|p| vec![p.x, p.y])
+            .collect::<Vec<f64>>();
+        for constraint in &self.constraints {
+            if let Constraint::PointOnCurve(p, curve) = constraint {
+                initial.push(self.nearest_t(*p, *curve));
+            }
+        }
+        let residuals = |x: &[f64]| self.residuals(x, count_point_variables);
+        let minimum = match levenberg_marquardt(residuals, &initial, tolerance, MAX_ITERATIONS) {
+            Ok(minimum) => minimum,
+            Err(MinimizeError::NotFinite) => return Err(ConstraintError::NotFinite),
+            Err(MinimizeError::NotConverged { best }) => {
+                return Err(ConstraintError::NotConverged {
+                    residual_norm: best.residual_norm,
+                })
+            }
+        };
+        // rank of jacobian at the solution
+        let count_variables = initial.len();
+        let count_equations = minimum.residuals.len();
+        let rank = forward_differences(&residuals, &minimum.x)
+            .qr()
+            .rank_with_tolerance(RANK_TOLERANCE);
+        let redundant_equations = count_equations - rank;
+        if minimum.residual_norm > tolerance {
+            return Err(ConstraintError::Inconsistent {
+                residual_norm: minimum.residual_norm,
+                redundant_equations,
+            });
+        }
+        self.points = to_points(&minimum.x[..count_point_variables]);
+        Ok(Solution {
+            residual_norm: minimum.residual_norm,
+            degrees_of_freedom: count_variables - rank,
+            redundant_equations,
+        })
+    }
+
+    fn curve_points(&self, points: &[Point], id: CurveId) -> Vec<Point> {
+        self.curves[id.0].iter().map(|p| points[p.0]).collect()
+    }
+
+    fn line_vec(&self, points: &[Point], id: LineId) -> Point {
+        let (origin, end) = self.lines[id.0];
+        points[end.0] - points[origin.0]
+    }
+
+    fn nearest_t(&self, p: PointId, curve: CurveId) -> f64 {
+        let curve_points = self.curve_points(&self.points, curve);
+        (0..=SAMPLES)
+            .map(|i| i as f64 / SAMPLES as f64)
+            .fold((0.0, f64::INFINITY), |(nearest, min), t| {
+                let d = point_at(&curve_points, t).distance(self.points[p.0]);
+                if d < min {
+                    (t, d)
+                } else {
+                    (nearest, min)
+                }
+            })
+            .0
+    }
+
+    /// Residuals of all constraints, which are 0 if satisfied
+    /// - x - x and y of points followed by t of points on curves
+    fn residuals(&self, x: &[f64], count_point_variables: usize) -> Vec<f64> {
+        let points = to_points(&x[..count_point_variables]);
+        let mut curve_parameters = x[count_point_variables..].iter();
+        let mut residuals = Vec::new();
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::Angle(l1, l2, angle_degree) => {
+                    // difference between the angle of lines and the target in radians,
+                    // which is not 0 where the second line points the opposite way unlike its sine
+                    let (u, v) = (
+                        self.line_vec(&points, *l1).normalize(),
+                        self.line_vec(&points, *l2).normalize(),
+                    );
+                    let (sin, cos) = angle_degree.to_radians().sin_cos();
+                    residuals.push(
+                        (cross(u, v) * cos - dot(u, v) * sin)
+                            .atan2(dot(u, v) * cos + cross(u, v) * sin),
+                    );
+                }
+                Constraint::Coincident(p1, p2) => {
+                    let d = points[p2.0] - points[p1.0];
+                    residuals.extend(vec![d.x, d.y]);
+                }
+                Constraint::CurveLength(curve, length) => {
+                    residuals.push(curve_length(&self.curve_points(&points, *curve)) - length)
+                }
+                Constraint::Distance(p1, p2, distance) => {
+                    residuals.push(points[p1.0].distance(points[p2.0]) - distance)
+                }
+                Constraint::Fixed(p, position) => {
+                    let d = points[p.0] - *position;
+                    residuals.extend(vec![d.x, d.y]);
+                }
+                Constraint::Horizontal(l) => residuals.push(self.line_vec(&points, *l).y),
+                Constraint::Parallel(l1, l2) => residuals.push(cross(
+                    self.line_vec(&points, *l1).normalize(),
+                    self.line_vec(&points, *l2).normalize(),
+                )),
+                Constraint::Perpendicular(l1, l2) => residuals.push(dot(
+                    self.line_vec(&points, *l1).normalize(),
+                    self.line_vec(&points, *l2).normalize(),
+                )),
+                Constraint::PointOnCurve(p, curve) => {
+                    let t = *curve_parameters.next().unwrap();
+                    let d = point_at(&self.curve_points(&points, *curve), t) - points[p.0];
+                    residuals.extend(vec![d.x, d.y]);
+                }
+                Constraint::Tangent(curve, curve_end, l) => {
+                    let curve_points = self.curve_points(&points, *curve);
+                    let n = curve_points.len();
+                    let tangent = match curve_end {
+                        CurveEnd::Origin => curve_points[1] - curve_points[0],
+                        CurveEnd::End => curve_points[n - 1] - curve_points[n - 2],
+                    };
+                    residuals.push(cross(
+                        tangent.normalize(),
+                        self.line_vec(&points, *l).normalize(),
+                    ));
+                }
+                Constraint::Vertical(l) => residuals.push(self.line_vec(&points, *l).x),
+            }
+        }
+        residuals
+    }
+}
+
+fn cross(u: Point, v: Point) -> f64 {
+    u.x * v.y - u.y * v.x
+}
+
+fn dot(u: Point, v: Point) -> f64 {
+    u.x * v.x + u.y * v.y
+}
+
+/// Length of the bezier curve of the points
+///
+/// integrated over fixed intervals so that the length changes smoothly with the points
+fn curve_length(points: &[Point]) -> f64 {
+    // derivative of a bezier curve is the bezier curve of differences of points
+    let n = points.len() - 1;
+    let differences = (0..n)
+        .map(|k| n as f64 * (points[k + 1] - points[k]))
+        .collect::<Vec<Point>>();
+    let dt = 1.0 / LENGTH_INTERVALS as f64;
+    (0..LENGTH_INTERVALS)
+        .map(|i| {
+            gauss_legendre(
+                &|t| point_at(&differences, t).norm(),
+                i as f64 * dt,
+                (i + 1) as f64 * dt,
+            )
+        })
+        .sum()
+}
+
+/// Point at t of the bezier curve of the points
+fn point_at(points: &[Point], t: f64) -> Point {
+    let n = points.len() - 1;
+    let binomials = binomial_row(n);
+    (0..=n).fold(Point::new(0.0, 0.0), |sum, k| {
+        sum + binomials[k] * (1.0 - t).powi((n - k) as i32) * t.powi(k as i32) * points[k]
+    })
+}
+
+fn to_points(x: &[f64]) -> Vec<Point> {
+    x.chunks(2).map(|xy| Point::new(xy[0], xy[1])).collect()
+}
+
+#[cfg(test)]
+mod test_constraint {
+    use crate::{
+        constraint::{Constraint, ConstraintError, PointId, Sketch, TOLERANCE},
+        shapes::point::Point,
+    };
+
+    /// right triangle of edges 3, 4, and 5, where the right angle is at the origin
+    fn right_triangle() -> (Sketch, PointId, PointId) {
+        let mut sketch = Sketch::new();
+        let a = sketch.add_point(Point::new(0.0, 0.0));
+        let b = sketch.add_point(Point::new(2.5, 0.5));
+        let c = sketch.add_point(Point::new(0.5, 3.5));
+        let (ab, ac) = (sketch.add_line(a, b), sketch.add_line(a, c));
+        sketch.constrain(Constraint::Fixed(a, Point::new(0.0, 0.0)));
+        sketch.constrain(Constraint::Horizontal(ab));
+        sketch.constrain(Constraint::Distance(a, b, 3.0));
+        sketch.constrain(Constraint::Distance(a, c, 4.0));
+        sketch.constrain(Constraint::Perpendicular(ab, ac));
+        (sketch, b, c)
+    }
+
+    fn assert_near(p: Point, q: Point) {
+        assert!(
+            p.distance(q) < 1e-5,
+            "({}, {}) != ({}, {})",
+            p.x,
+            p.y,
+            q.x,
+            q.y
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let (mut sketch, b, c) = right_triangle();
+        let solution = sketch.solve(TOLERANCE).expect("Failed to solve");
+        assert_near(sketch.point(b), Point::new(3.0, 0.0));
+        assert_near(sketch.point(c), Point::new(0.0, 4.0));
+        assert_eq!(solution.degrees_of_freedom, 0);
+        assert_eq!(solution.redundant_equations, 0);
+    }
+
+    #[test]
+    fn test_solve_angle() {
+        let mut sketch = Sketch::new();
+        let a = sketch.add_point(Point::new(0.0, 0.0));
+        let b = sketch.add_point(Point::new(1.0, 0.0));
+        // nearly the opposite of the angle, where the sine of the difference is nearly 0
+        let c = sketch.add_point(Point::new(0.1, -1.0));
+        let (ab, ac) = (sketch.add_line(a, b), sketch.add_line(a, c));
+        sketch.constrain(Constraint::Fixed(a, Point::new(0.0, 0.0)));
+        sketch.constrain(Constraint::Fixed(b, Point::new(1.0, 0.0)));
+        sketch.constrain(Constraint::Distance(a, c, 1.0));
+        sketch.constrain(Constraint::Angle(ab, ac, 90.0));
+        sketch.solve(TOLERANCE).expect("Failed to solve");
+        assert_near(sketch.point(c), Point::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_solve_over_constrained() {
+        // the hypotenuse is implied by the other constraints
+        let (mut sketch, b, c) = right_triangle();
+        sketch.constrain(Constraint::Distance(b, c, 5.0));
+        let solution = sketch.solve(TOLERANCE).expect("Failed to solve");
+        assert_near(sketch.point(c), Point::new(0.0, 4.0));
+        assert_eq!(solution.degrees_of_freedom, 0);
+        assert_eq!(solution.redundant_equations, 1);
+        // the hypotenuse conflicts with the other constraints
+        let (mut sketch, b, c) = right_triangle();
+        sketch.constrain(Constraint::Distance(b, c, 6.0));
+        match sketch.solve(TOLERANCE) {
+            Err(ConstraintError::Inconsistent {
+                residual_norm,
+                redundant_equations,
+            }) => {
+                assert!(residual_norm > TOLERANCE);
+                assert_eq!(redundant_equations, 1);
+            }
+            _ => panic!("Conflicting constraints are solved"),
+        }
+    }
+
+    #[test]
+    fn test_solve_under_constrained() {
+        // the second point can go around the circle of radius 2
+        let mut sketch = Sketch::new();
+        let a = sketch.add_point(Point::new(1.0, 1.0));
+        let b = sketch.add_point(Point::new(2.0, 2.0));
+        sketch.constrain(Constraint::Fixed(a, Point::new(1.0, 1.0)));
+        sketch.constrain(Constraint::Distance(a, b, 2.0));
+        let solution = sketch.solve(TOLERANCE).expect("Failed to solve");
+        assert!((sketch.point(a).distance(sketch.point(b)) - 2.0).abs() < 1e-5);
+        assert_eq!(solution.degrees_of_freedom, 1);
+        assert_eq!(solution.redundant_equations, 0);
+    }
+}
@@ -0,0 +1,357 @@
+use pmmath::polynomial::Polynomial;
+
+use crate::shapes::{
+    bezier::{Bezier, TOLERANCE},
+    circle::Circle,
+    geometry::Geometry,
+    line::Line,
+    point::Point,
+};
+
+/// Why a construction has no definite answer
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstructionError {
+    /// given points are the same where they must differ, or a line has no length
+    Degenerate,
+    /// solutions are not isolated, e.g. two circles are the same
+    InfinitelyMany,
+    /// the shapes do not meet, e.g. a line is farther from the center of a circle than the radius
+    NoSolution,
+    /// the length is out of the shape
+    OutOfRange,
+}
+
+/// Line of the length 1 from the vertex which halves the angle from a to b around the vertex
+///
+/// if a and b are on the opposite sides of the vertex, the bisector goes to the left of the direction to a
+pub fn angle_bisector(vertex: Point, a: Point, b: Point) -> Result<Line, ConstructionError> {
+    if vertex.distance(a) <= TOLERANCE || vertex.distance(b) <= TOLERANCE {
+        return Err(ConstructionError::Degenerate);
+    }
+    let (u1, u2) = ((a - vertex).normalize(), (b - vertex).normalize());
+    let sum = u1 + u2;
+    let direction = if sum.norm() > TOLERANCE {
+        sum.normalize()
+    } else {
+        Point::new(-u1.y, u1.x)
+    };
+    Ok(Line::new(vertex, vertex + direction))
+}
+
+/// Points where two circles meet, where the first one is on the left of the direction from the first center to the second
+///
+/// circles touching each other within the tolerance meet at a point
+pub fn circle_circle(c1: &Circle, c2: &Circle) -> Result<Vec<Point>, ConstructionError> {
+    let v = c2.origin - c1.origin;
+    let d = v.norm();
+    if d <= TOLERANCE {
+        return Err(if (c1.r - c2.r).abs() <= TOLERANCE {
+            ConstructionError::InfinitelyMany
+        } else {
+            ConstructionError::NoSolution
+        });
+    }
+    if d > c1.r + c2.r + TOLERANCE || d < (c1.r - c2.r).abs() - TOLERANCE {
+        return Err(ConstructionError::NoSolution);
+    }
+    let u = v / d;
+    // distance from the first center to the chord between the points, and half of the chord
+    let a = (d * d + c1.r * c1.r - c2.r * c2.r) / (2.0 * d);
+    let h = (c1.r * c1.r - a * a).max(0.0).sqrt();
+    let middle = c1.origin + a * u;
+    if h <= TOLERANCE {
+        return Ok(vec![middle]);
+    }
+    let left = Point::new(-u.y, u.x);
+    Ok(vec![middle + h * left, middle - h * left])
+}
+
+/// Points on the infinite line at the radius from the center of the circle, in the direction of the line
+///
+/// it is the classic construction of a point on a line at a given distance from another point.
+/// a line touching the circle within the tolerance meets it at the foot of the perpendicular
+pub fn circle_line(c: &Circle, l: &Line) -> Result<Vec<Point>, ConstructionError> {
+    let foot = perpendicular_foot(c.origin, l)?;
+    let d = foot.distance(c.origin);
+    if d > c.r + TOLERANCE {
+        return Err(ConstructionError::NoSolution);
+    }
+    let h = (c.r * c.r - d * d).max(0.0).sqrt();
+    if h <= TOLERANCE {
+        return Ok(vec![foot]);
+    }
+    let u = l.vec().normalize();
+    Ok(vec![foot - h * u, foot + h * u])
+}
+
+/// Foot of the perpendicular from p to the infinite line
+pub fn perpendicular_foot(p: Point, l: &Line) -> Result<Point, ConstructionError> {
+    if l.len() <= TOLERANCE {
+        return Err(ConstructionError::Degenerate);
+    }
+    Ok(l.project_infinite(p).point)
+}
+
+/// Point at the length along the shape from its start
+pub fn point_along(shape: &dyn Geometry, length: f64) -> Result<Point, ConstructionError> {
+    if length < 0.0 || length > shape.length() + TOLERANCE {
+        return Err(ConstructionError::OutOfRange);
+    }
+    Ok(shape.point_at_length(length.min(shape.length())))
+}
+
+/// Points on the circle where lines from p touch it, where the first one is on the left of the direction from p to the center
+///
+/// p on the circle within the tolerance is the only point
+pub fn tangents_to_circle(p: Point, c: &Circle) -> Result<Vec<Point>, ConstructionError> {
+    let v = p - c.origin;
+    let d = v.norm();
+    if d < c.r - TOLERANCE {
+        return Err(ConstructionError::NoSolution);
+    }
+    if d <= c.r + TOLERANCE {
+        return Ok(vec![p]);
+    }
+    let u = v / d;
+    let left = Point::new(-u.y, u.x);
+    // angle between the direction to p and the direction to each point from the center
+    let (cos, sin) = (c.r / d, (1.0 - (c.r / d).powi(2)).sqrt());
+    Ok(vec![
+        c.origin + c.r * (cos * u - sin * left),
+        c.origin + c.r * (cos * u + sin * left),
+    ])
+}
+
+/// Points on the curve where lines from p touch it, in ascending order of t
+///
+/// they are roots of (B(t) - p) x B'(t) in the range, except p itself if it is on the curve
+pub fn tangents_to_curve(p: Point, b: &Bezier) -> Result<Vec<Point>, ConstructionError> {
+    let (x_t, y_t) = b.polynomials();
+    let (dx, dy) = (x_t.derivative(), y_t.derivative());
+    let (x_t, y_t) = (
+        &x_t - Polynomial::new(vec![p.x]),
+        &y_t - Polynomial::new(vec![p.y]),
+    );
+    let cross = &x_t * dy - &y_t * dx;
+    let points = b
+        .roots_in_range(&cross)
+        .into_iter()
+        .map(|t| b.point_at(t))
+        .filter(|point| point.distance(p) > TOLERANCE)
+        .collect::<Vec<Point>>();
+    if points.is_empty() {
+        Err(ConstructionError::NoSolution)
+    } else {
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod test_construction {
+    use crate::{
+        construction::{
+            angle_bisector, circle_circle, circle_line, perpendicular_foot, point_along,
+            tangents_to_circle, tangents_to_curve, ConstructionError,
+        },
+        shapes::{
+            bezier::{Bezier, TOLERANCE},
+            circle::Circle,
+            line::Line,
+            point::Point,
+            Shape,
+        },
+    };
+
+    fn assert_points(points: Vec<Point>, expected: Vec<Point>) {
+        assert_eq!(points.len(), expected.len());
+        for (p, q) in points.iter().zip(expected.iter()) {
+            assert!(p.distance(*q) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_angle_bisector() {
+        let origin = Point::new(1.0, 1.0);
+        let bisector = angle_bisector(origin, Point::new(3.0, 1.0), Point::new(1.0, 5.0)).unwrap();
+        assert!(bisector.origin == origin);
+        let h = 0.5f64.sqrt();
+        assert!(bisector.end.distance(Point::new(1.0 + h, 1.0 + h)) < 1e-9);
+        // on the opposite sides, it goes to the left of the direction to a
+        let bisector = angle_bisector(origin, Point::new(3.0, 1.0), Point::new(-1.0, 1.0)).unwrap();
+        assert!(bisector.end.distance(Point::new(1.0, 2.0)) < 1e-9);
+        assert_eq!(
+            angle_bisector(origin, origin, Point::new(1.0, 5.0))
+                .err()
+                .unwrap(),
+            ConstructionError::Degenerate
+        );
+    }
+
+    #[test]
+    fn test_circle_circle() {
+        let c1 = Circle::new(Point::new(0.0, 0.0), 5.0);
+        // the first point is on the left of the direction from the first center to the second
+        let c2 = Circle::new(Point::new(8.0, 0.0), 5.0);
+        assert_points(
+            circle_circle(&c1, &c2).unwrap(),
+            vec![Point::new(4.0, 3.0), Point::new(4.0, -3.0)],
+        );
+        assert_points(
+            circle_circle(&c2, &c1).unwrap(),
+            vec![Point::new(4.0, -3.0), Point::new(4.0, 3.0)],
+        );
+        // touching from the outside and from the inside
+        let outside = Circle::new(Point::new(0.0, 8.0), 3.0);
+        assert_points(
+            circle_circle(&c1, &outside).unwrap(),
+            vec![Point::new(0.0, 5.0)],
+        );
+        let inside = Circle::new(Point::new(2.0, 0.0), 3.0);
+        assert_points(
+            circle_circle(&c1, &inside).unwrap(),
+            vec![Point::new(5.0, 0.0)],
+        );
+        // apart, one inside the other, and the same circle
+        for (c2, error) in [
+            (
+                Circle::new(Point::new(11.0, 0.0), 5.0),
+                ConstructionError::NoSolution,
+            ),
+            (
+                Circle::new(Point::new(1.0, 0.0), 2.0),
+                ConstructionError::NoSolution,
+            ),
+            (
+                Circle::new(Point::new(0.0, 0.0), 2.0),
+                ConstructionError::NoSolution,
+            ),
+            (
+                Circle::new(Point::new(0.0, 0.0), 5.0),
+                ConstructionError::InfinitelyMany,
+            ),
+        ]
+        .iter()
+        {
+            assert_eq!(circle_circle(&c1, c2).err().unwrap(), *error);
+        }
+    }
+
+    #[test]
+    fn test_circle_line() {
+        let c = Circle::new(Point::new(1.0, 3.0), 5.0);
+        // in the direction of the line, even beyond its edges
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        let expected = vec![Point::new(-3.0, 0.0), Point::new(5.0, 0.0)];
+        assert_points(circle_line(&c, &l).unwrap(), expected.clone());
+        let mut reversed = l;
+        reversed.reverse();
+        assert_points(
+            circle_line(&c, &reversed).unwrap(),
+            expected.into_iter().rev().collect(),
+        );
+        // the first and the last are the left and the right widths of a short sleeve
+        let points = circle_line(&Circle::new(Point::new(0.0, 4.0), 5.0), &l).unwrap();
+        assert!(points[0].x < 0.0 && points.last().unwrap().x > 0.0);
+        // touching, apart and degenerate
+        let tangent = Line::new(Point::new(6.0, 0.0), Point::new(6.0, 1.0));
+        assert_points(
+            circle_line(&c, &tangent).unwrap(),
+            vec![Point::new(6.0, 3.0)],
+        );
+        let apart = Line::new(Point::new(7.0, 0.0), Point::new(7.0, 1.0));
+        assert_eq!(
+            circle_line(&c, &apart).err().unwrap(),
+            ConstructionError::NoSolution
+        );
+        let point = Line::new(Point::new(1.0, 1.0), Point::new(1.0, 1.0));
+        assert_eq!(
+            circle_line(&c, &point).err().unwrap(),
+            ConstructionError::Degenerate
+        );
+    }
+
+    #[test]
+    fn test_perpendicular_foot() {
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(2.0, 2.0));
+        let foot = perpendicular_foot(Point::new(0.0, 6.0), &l).unwrap();
+        assert!(foot.distance(Point::new(3.0, 3.0)) < 1e-9);
+        let point = Line::new(Point::new(1.0, 1.0), Point::new(1.0, 1.0));
+        assert_eq!(
+            perpendicular_foot(Point::new(0.0, 6.0), &point)
+                .err()
+                .unwrap(),
+            ConstructionError::Degenerate
+        );
+    }
+
+    #[test]
+    fn test_point_along() {
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(3.0, 4.0));
+        assert!(point_along(&l, 2.5).unwrap().distance(Point::new(1.5, 2.0)) < 1e-9);
+        // the end within the tolerance
+        assert!(point_along(&l, 5.0 + 1e-4).unwrap().distance(l.end) < 1e-9);
+        for length in [-0.1, 5.1].iter() {
+            assert_eq!(
+                point_along(&l, *length).err().unwrap(),
+                ConstructionError::OutOfRange
+            );
+        }
+        let c = Shape::Circle(Circle::new(Point::new(0.0, 0.0), 1.0));
+        let quarter = point_along(&c, std::f64::consts::PI / 2.0).unwrap();
+        assert!(quarter.distance(Point::new(0.0, 1.0)) < 1e-6);
+    }
+
+    #[test]
+    fn test_tangents_to_circle() {
+        let c = Circle::new(Point::new(0.0, 0.0), 1.0);
+        // the first is on the left of the direction from p to the center
+        let h = 0.5f64.sqrt();
+        assert_points(
+            tangents_to_circle(Point::new(2.0f64.sqrt(), 0.0), &c).unwrap(),
+            vec![Point::new(h, -h), Point::new(h, h)],
+        );
+        // lines from the points to p touch the circle
+        let p = Point::new(-3.0, 2.0);
+        for point in tangents_to_circle(p, &c).unwrap() {
+            let (radius, tangent) = (point - c.origin, p - point);
+            assert!((radius.x * tangent.x + radius.y * tangent.y).abs() < 1e-9);
+        }
+        assert_points(
+            tangents_to_circle(Point::new(0.0, 1.0), &c).unwrap(),
+            vec![Point::new(0.0, 1.0)],
+        );
+        assert_eq!(
+            tangents_to_circle(Point::new(0.5, 0.0), &c).err().unwrap(),
+            ConstructionError::NoSolution
+        );
+    }
+
+    #[test]
+    fn test_tangents_to_curve() {
+        // y = x^2 from x = -2 to 2, whose tangents through (0, -1) touch it at x = -1 and 1
+        let parabola = Bezier::new_with_ctrl_points(vec![
+            Point::new(-2.0, 4.0),
+            Point::new(0.0, -4.0),
+            Point::new(2.0, 4.0),
+        ]);
+        // within the tolerance of roots
+        let points = tangents_to_curve(Point::new(0.0, -1.0), &parabola).unwrap();
+        assert_eq!(points.len(), 2);
+        assert!(points[0].distance(Point::new(-1.0, 1.0)) < TOLERANCE);
+        assert!(points[1].distance(Point::new(1.0, 1.0)) < TOLERANCE);
+        // from a point on the curve, only the other points count
+        assert_eq!(
+            tangents_to_curve(Point::new(1.0, 1.0), &parabola)
+                .err()
+                .unwrap(),
+            ConstructionError::NoSolution
+        );
+        // inside the curve
+        assert_eq!(
+            tangents_to_curve(Point::new(0.0, 2.0), &parabola)
+                .err()
+                .unwrap(),
+            ConstructionError::NoSolution
+        );
+    }
+}
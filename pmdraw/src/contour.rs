@@ -0,0 +1,522 @@
+use std::f64::consts::PI;
+
+use pmmath::polynomial::Polynomial;
+
+use crate::{
+    boolean::{boolean, BooleanError, Operation},
+    corner::{chamfer, fillet, CornerError},
+    shapes::{
+        bezier::{Bezier, TOLERANCE},
+        circle::Circle,
+        geometry::Geometry,
+        intersection::Intersections,
+        line::Line,
+        point::Point,
+        Shape,
+    },
+};
+
+/// Closed outline made of segments, where each segment ends at the start of the next one and the last ends at the start of the first
+#[derive(Clone)]
+pub struct Contour {
+    segments: Vec<Segment>,
+}
+
+/// Part of a contour, which is any shape going from its start to its end
+#[derive(Clone)]
+pub struct Segment {
+    shape: Shape,
+}
+
+/// Direction in which a contour goes around
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Gap between the end of a segment and the start of the next one
+#[derive(Copy, Clone)]
+pub struct Gap {
+    /// index of the segment before the gap
+    pub index: usize,
+    pub distance: f64,
+}
+
+/// Two segments which meet somewhere other than the joint they share
+#[derive(Clone)]
+pub struct SelfIntersection {
+    /// indices of the segments, where i < j
+    pub i: usize,
+    pub j: usize,
+    /// where they meet, where t1 is on the segment i and t2 is on the segment j
+    pub intersections: Intersections,
+}
+
+impl Contour {
+    /// Signed area enclosed by the contour, which is positive if the contour goes counterclockwise
+    ///
+    /// it is the sum of (x dy - y dx) / 2 along segments by green's theorem, which is exact for any segment
+    pub fn area(&self) -> f64 {
+        self.segments.iter().map(|s| s.area()).sum()
+    }
+
+    /// Cut off the corner at the end of the segment at the index by a line
+    ///
+    /// - distance - length along each segment from the corner to where the line meets it
+    pub fn chamfer(&self, index: usize, distance: f64) -> Result<Contour, CornerError> {
+        let next = self.next_index(index);
+        let chamfer = chamfer(&self.segments[index], &self.segments[next], distance)?;
+        Ok(self.replace_corner(
+            index,
+            chamfer.fst,
+            Segment::new(Shape::Line(chamfer.line)),
+            chamfer.snd,
+        ))
+    }
+
+    /// true if p is inside the contour, where the contour is approximated by lines within the tolerance
+    ///
+    /// inside means the contour winds around p, thus points enclosed twice by a contour crossing itself are also inside
+    pub fn contains(&self, p: Point) -> bool {
+        self.winding_number(p) != 0
+    }
+
+    /// Region inside this contour but outside another, as contours whose holes go clockwise
+    pub fn difference(&self, another: &Contour) -> Result<Vec<Contour>, BooleanError> {
+        boolean(self, another, Operation::Difference)
+    }
+
+    /// Contour of lines within the tolerance from this contour
+    pub fn flatten(&self, tolerance: f64) -> Contour {
+        Contour::new(
+            self.segments
+                .iter()
+                .flat_map(|segment| {
+                    segment
+                        .shape()
+                        .flatten(tolerance)
+                        .windows(2)
+                        .map(|points| Segment::new(Shape::Line(Line::new(points[0], points[1]))))
+                        .collect::<Vec<Segment>>()
+                })
+                .collect(),
+        )
+    }
+
+    /// Round the corner at the end of the segment at the index by an arc of the radius
+    pub fn fillet(&self, index: usize, radius: f64) -> Result<Contour, CornerError> {
+        let next = self.next_index(index);
+        let fillet = fillet(&self.segments[index], &self.segments[next], radius)?;
+        Ok(self.replace_corner(
+            index,
+            fillet.fst,
+            Segment::new(Shape::Arc(fillet.arc)),
+            fillet.snd,
+        ))
+    }
+
+    /// Gaps between the end of each segment and the start of the next one, which are longer than the tolerance
+    pub fn gaps(&self) -> Vec<Gap> {
+        let n = self.segments.len();
+        (0..n)
+            .map(|i| Gap {
+                index: i,
+                distance: self.segments[i]
+                    .end()
+                    .distance(self.segments[(i + 1) % n].start()),
+            })
+            .filter(|gap| gap.distance > TOLERANCE)
+            .collect()
+    }
+
+    /// Region inside both this contour and another, as contours whose holes go clockwise
+    pub fn intersection(&self, another: &Contour) -> Result<Vec<Contour>, BooleanError> {
+        boolean(self, another, Operation::Intersection)
+    }
+
+    /// true if every segment ends at the start of the next one
+    pub fn is_closed(&self) -> bool {
+        self.gaps().is_empty()
+    }
+
+    pub fn new(segments: Vec<Segment>) -> Contour {
+        Contour { segments }
+    }
+
+    /// Direction in which the contour goes around, which is decided by the sign of the area
+    pub fn orientation(&self) -> Orientation {
+        if self.area() < 0.0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::CounterClockwise
+        }
+    }
+
+    /// Total length of segments
+    pub fn perimeter(&self) -> f64 {
+        self.segments.iter().map(|s| s.len()).sum()
+    }
+
+    /// Go around the other way, reversing the order of segments and each segment
+    pub fn reverse(&mut self) {
+        self.segments.reverse();
+        for segment in self.segments.iter_mut() {
+            segment.reverse();
+        }
+    }
+
+    pub fn segments(&self) -> &Vec<Segment> {
+        &self.segments
+    }
+
+    /// Pairs of segments which meet other than at their joint, i.e. where the contour crosses or touches itself
+    pub fn self_intersections(&self) -> Vec<SelfIntersection> {
+        let n = self.segments.len();
+        let mut self_intersections = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                // joints shared by adjacent segments
+                let mut joints = Vec::new();
+                if j == i + 1 {
+                    joints.push(self.segments[i].end());
+                }
+                if i == 0 && j == n - 1 {
+                    joints.push(self.segments[0].start());
+                }
+                let intersections =
+                    match self.segments[i].shape().intersect(self.segments[j].shape()) {
+                        Intersections::Points(points) => Intersections::Points(
+                            points
+                                .into_iter()
+                                .filter(|each| {
+                                    joints
+                                        .iter()
+                                        .all(|joint| joint.distance(each.point) > TOLERANCE)
+                                })
+                                .collect(),
+                        ),
+                        intersections => intersections,
+                    };
+                let meets = match &intersections {
+                    Intersections::Points(points) => !points.is_empty(),
+                    _ => true,
+                };
+                if meets {
+                    self_intersections.push(SelfIntersection {
+                        i,
+                        j,
+                        intersections,
+                    });
+                }
+            }
+        }
+        self_intersections
+    }
+
+    /// Region inside either this contour or another, as contours whose holes go clockwise
+    pub fn union(&self, another: &Contour) -> Result<Vec<Contour>, BooleanError> {
+        boolean(self, another, Operation::Union)
+    }
+
+    /// Number of times the contour goes around p counterclockwise, which is negative if it goes clockwise
+    ///
+    /// the contour is approximated by lines within the tolerance
+    pub fn winding_number(&self, p: Point) -> i32 {
+        let mut winding_number = 0;
+        for segment in &self.segments {
+            for edge in segment.shape().flatten(TOLERANCE).windows(2) {
+                let (a, b) = (edge[0], edge[1]);
+                // positive if p is on the left of the edge
+                let side = (b.x - a.x) * (p.y - a.y) - (p.x - a.x) * (b.y - a.y);
+                if a.y <= p.y && p.y < b.y && side > 0.0 {
+                    winding_number += 1;
+                } else if b.y <= p.y && p.y < a.y && side < 0.0 {
+                    winding_number -= 1;
+                }
+            }
+        }
+        winding_number
+    }
+
+    /// Region inside just one of this contour and another, as contours whose holes go clockwise
+    pub fn xor(&self, another: &Contour) -> Result<Vec<Contour>, BooleanError> {
+        boolean(self, another, Operation::Xor)
+    }
+
+    /// Index of the segment following the one at the index, where the first one follows the last
+    ///
+    /// panics if the contour has less than two segments, which have no corner between different segments
+    fn next_index(&self, index: usize) -> usize {
+        assert!(self.segments.len() >= 2, "A corner needs two segments");
+        (index + 1) % self.segments.len()
+    }
+
+    /// Contour where segments meeting at the end of the segment at the index are replaced by trimmed ones,
+    /// and the joint is put between them
+    fn replace_corner(&self, index: usize, fst: Segment, joint: Segment, snd: Segment) -> Contour {
+        let next = self.next_index(index);
+        let mut segments = self.segments.clone();
+        segments[index] = fst;
+        segments[next] = snd;
+        segments.insert(index + 1, joint);
+        Contour::new(segments)
+    }
+}
+
+impl Segment {
+    /// Integral of (x dy - y dx) / 2 along the segment, whose sum around a contour is the area
+    pub fn area(&self) -> f64 {
+        let cross = |a: Point, b: Point| a.x * b.y - a.y * b.x;
+        match &self.shape {
+            Shape::Line(l) => cross(l.origin, l.end) / 2.0,
+            Shape::Bezier(b) => bezier_area(b),
+            Shape::Spline(s) => s.segments().iter().map(bezier_area).sum(),
+            // P = O + Q(theta) where Q x Q' is constant and the integral of Q' is P(theta1) - P(theta0)
+            Shape::Arc(a) => {
+                (cross(a.origin, a.end() - a.start()) + a.r * a.r * a.sweep_angle.to_radians())
+                    / 2.0
+            }
+            Shape::EllipticalArc(e) => {
+                (cross(e.origin, e.end() - e.start()) + e.rx * e.ry * e.sweep_angle.to_radians())
+                    / 2.0
+            }
+            // a circle goes counterclockwise
+            Shape::Circle(c) => PI * c.r * c.r,
+            Shape::Point(_) => 0.0,
+        }
+    }
+
+    pub fn end(&self) -> Point {
+        self.shape.end()
+    }
+
+    pub fn len(&self) -> f64 {
+        self.shape.length()
+    }
+
+    pub fn new(shape: Shape) -> Segment {
+        Segment { shape }
+    }
+
+    /// Segments at the distance from the segment, on the right side of its direction if positive and the left if negative
+    ///
+    /// offsets of lines, arcs, and circles are exact, and the others are approximated by curves within the tolerance
+    pub fn offset(&self, distance: f64) -> Vec<Segment> {
+        let beziers = |beziers: Vec<Bezier>| {
+            beziers
+                .into_iter()
+                .map(|b| Segment::new(Shape::Bezier(b)))
+                .collect()
+        };
+        match &self.shape {
+            Shape::Line(l) => {
+                let v = l.vec().normalize();
+                let right = distance * Point::new(v.y, -v.x);
+                vec![Segment::new(Shape::Line(Line::new(
+                    l.origin + right,
+                    l.end + right,
+                )))]
+            }
+            Shape::Bezier(b) => beziers(b.offset(distance, TOLERANCE)),
+            Shape::Spline(s) => beziers(s.offset(distance, TOLERANCE)),
+            Shape::Arc(a) => a
+                .offset(distance)
+                .map(|a| Segment::new(Shape::Arc(a)))
+                .into_iter()
+                .collect(),
+            Shape::EllipticalArc(e) => beziers(e.offset(distance, TOLERANCE)),
+            // the right side of a circle is outside since it goes counterclockwise
+            Shape::Circle(c) if c.r + distance > 0.0 => {
+                vec![Segment::new(Shape::Circle(Circle::new(
+                    c.origin,
+                    c.r + distance,
+                )))]
+            }
+            Shape::Circle(_) | Shape::Point(_) => Vec::new(),
+        }
+    }
+
+    /// Point at the parameter t, which is what `point_at` of the shape takes
+    pub fn point_at(&self, t: f64) -> Point {
+        self.shape.point_at(t)
+    }
+
+    pub fn reverse(&mut self) {
+        self.shape.reverse()
+    }
+
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    pub fn start(&self) -> Point {
+        self.shape.start()
+    }
+
+    /// Part of the segment from t0 to t1
+    pub fn sub_segment(&self, t0: f64, t1: f64) -> Segment {
+        Segment::new(self.shape.sub_shape(t0, t1))
+    }
+
+    /// Parameter of the point at the length along the segment from the start
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        self.shape.t_at_length(length)
+    }
+
+    /// Parameters at the start and the end
+    pub fn t_range(&self) -> (f64, f64) {
+        self.shape.t_range()
+    }
+
+    /// Unit tangent at the parameter t in the direction from the start to the end
+    pub fn tangent(&self, t: f64) -> Point {
+        self.shape.tangent(t)
+    }
+}
+
+/// Integral of (x dy - y dx) / 2 along the range of the curve
+fn bezier_area(b: &Bezier) -> f64 {
+    let (x_t, y_t) = b.polynomials();
+    let integrand = &x_t * y_t.derivative() - &y_t * x_t.derivative();
+    let range = b.t_range();
+    integral(&integrand, range.from, range.to) / 2.0
+}
+
+/// Definite integral of p from a to b
+fn integral(p: &Polynomial, a: f64, b: f64) -> f64 {
+    let antiderivative = Polynomial::new(
+        std::iter::once(0.0)
+            .chain(
+                p.coefficients
+                    .iter()
+                    .enumerate()
+                    .map(|(k, c)| c / (k + 1) as f64),
+            )
+            .collect(),
+    );
+    antiderivative.eval(b) - antiderivative.eval(a)
+}
+
+#[cfg(test)]
+mod test_contour {
+    use std::f64::consts::PI;
+
+    use crate::{
+        contour::{Contour, Orientation, Segment},
+        shapes::{
+            arc::Arc, bezier::Bezier, circle::Circle, ellipse::EllipticalArc,
+            intersection::Intersections, line::Line, point::Point, Shape,
+        },
+    };
+
+    /// contour of lines through the corners in order
+    fn polygon(corners: Vec<(f64, f64)>) -> Contour {
+        let n = corners.len();
+        let corner = |i: usize| Point::new(corners[i % n].0, corners[i % n].1);
+        Contour::new(
+            (0..n)
+                .map(|i| Segment::new(Shape::Line(Line::new(corner(i), corner(i + 1)))))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_area() {
+        let mut square = polygon(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(square.area(), 4.0);
+        assert_eq!(square.orientation(), Orientation::CounterClockwise);
+        square.reverse();
+        assert_eq!(square.area(), -4.0);
+        assert_eq!(square.orientation(), Orientation::Clockwise);
+        // between y = x^2 and y = 1
+        let parabola = Contour::new(vec![
+            Segment::new(Shape::Bezier(Bezier::new_with_ctrl_points(vec![
+                Point::new(-1.0, 1.0),
+                Point::new(0.0, -1.0),
+                Point::new(1.0, 1.0),
+            ]))),
+            Segment::new(Shape::Line(Line::new(
+                Point::new(1.0, 1.0),
+                Point::new(-1.0, 1.0),
+            ))),
+        ]);
+        assert!((parabola.area() - 4.0 / 3.0).abs() < 1e-12);
+        // half of a disk, going clockwise
+        let half_disk = Contour::new(vec![
+            Segment::new(Shape::Arc(Arc::new(
+                Point::new(1.0, 1.0),
+                1.0,
+                180.0,
+                -180.0,
+            ))),
+            Segment::new(Shape::Line(Line::new(
+                Point::new(2.0, 1.0),
+                Point::new(0.0, 1.0),
+            ))),
+        ]);
+        assert!((half_disk.area() + PI / 2.0).abs() < 1e-12);
+        let circle = Contour::new(vec![Segment::new(Shape::Circle(Circle::new(
+            Point::new(3.0, 3.0),
+            2.0,
+        )))]);
+        assert!((circle.area() - 4.0 * PI).abs() < 1e-12);
+        let ellipse = Contour::new(vec![Segment::new(Shape::EllipticalArc(
+            EllipticalArc::ellipse(Point::new(3.0, 3.0), 2.0, 1.0, 30.0),
+        ))]);
+        assert!((ellipse.area() - 2.0 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_winding_number() {
+        let mut square = polygon(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert_eq!(square.winding_number(Point::new(1.0, 1.0)), 1);
+        assert_eq!(square.winding_number(Point::new(3.0, 1.0)), 0);
+        assert!(square.contains(Point::new(1.0, 1.0)) && !square.contains(Point::new(-1.0, 1.0)));
+        square.reverse();
+        assert_eq!(square.winding_number(Point::new(1.0, 1.0)), -1);
+        // going around twice
+        let circle = Segment::new(Shape::Circle(Circle::new(Point::new(0.0, 0.0), 1.0)));
+        let twice = Contour::new(vec![circle.clone(), circle]);
+        assert_eq!(twice.winding_number(Point::new(0.5, 0.0)), 2);
+        assert_eq!(twice.winding_number(Point::new(0.0, 1.5)), 0);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let square = polygon(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert!(square.gaps().is_empty() && square.is_closed());
+        // the second edge ends short of the third one
+        let mut segments = square.segments().clone();
+        segments[1] = Segment::new(Shape::Line(Line::new(
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 1.5),
+        )));
+        let open = Contour::new(segments);
+        let gaps = open.gaps();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].index, 1);
+        assert!((gaps[0].distance - 0.5).abs() < 1e-12);
+        assert!(!open.is_closed());
+    }
+
+    #[test]
+    fn test_self_intersections() {
+        let square = polygon(vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+        assert!(square.self_intersections().is_empty());
+        // bow tie, whose diagonals cross at the middle
+        let bow_tie = polygon(vec![(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)]);
+        let crossings = bow_tie.self_intersections();
+        assert_eq!(crossings.len(), 1);
+        assert!(crossings[0].i == 0 && crossings[0].j == 2);
+        let points = crossings[0].intersections.points();
+        assert_eq!(points.len(), 1);
+        assert!(points[0].point.distance(Point::new(1.0, 1.0)) < 1e-12);
+        // the second edge goes back along the first one
+        let folded = polygon(vec![(0.0, 0.0), (2.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+        let overlaps = folded.self_intersections();
+        assert!(overlaps.iter().any(|each| each.i == 0
+            && each.j == 1
+            && matches!(each.intersections, Intersections::Coincident)));
+    }
+}
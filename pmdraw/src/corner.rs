@@ -0,0 +1,234 @@
+use crate::{
+    contour::Segment,
+    shapes::{
+        arc::Arc, bezier::TOLERANCE, geometry::Geometry, intersection::Intersections, line::Line,
+    },
+};
+
+/// segments meet smoothly if the sine of the angle between their directions is smaller than this
+const TANGENT_TOLERANCE: f64 = 1e-3;
+
+/// Corner rounded by an arc tangent to both segments
+#[derive(Clone)]
+pub struct Fillet {
+    /// the first segment trimmed where the arc starts
+    pub fst: Segment,
+    pub arc: Arc,
+    /// the second segment trimmed where the arc ends
+    pub snd: Segment,
+}
+
+/// Corner cut off by a line
+#[derive(Clone)]
+pub struct Chamfer {
+    /// the first segment trimmed where the line starts
+    pub fst: Segment,
+    pub line: Line,
+    /// the second segment trimmed where the line ends
+    pub snd: Segment,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CornerError {
+    /// the first segment does not end at the start of the second one
+    NotAdjacent,
+    /// the segments meet in the same or the opposite direction, thus there is no corner
+    NoCorner,
+    /// the radius or the distance does not fit in the segments
+    TooLarge,
+}
+
+/// Cut off the corner where the first segment ends and the second one starts
+///
+/// - distance - length along each segment from the corner to where the line meets it
+pub fn chamfer(fst: &Segment, snd: &Segment, distance: f64) -> Result<Chamfer, CornerError> {
+    turn(fst, snd)?;
+    let (fst_len, snd_len) = (fst.len(), snd.len());
+    if distance <= 0.0 || distance >= fst_len || distance >= snd_len {
+        return Err(CornerError::TooLarge);
+    }
+    let fst = fst.sub_segment(fst.t_range().0, fst.t_at_length(fst_len - distance));
+    let snd = snd.sub_segment(snd.t_at_length(distance), snd.t_range().1);
+    Ok(Chamfer {
+        line: Line::new(fst.end(), snd.start()),
+        fst,
+        snd,
+    })
+}
+
+/// Round the corner where the first segment ends and the second one starts by an arc of the radius
+///
+/// the center of the arc is where offsets of the segments at the radius meet inside the corner,
+/// and the arc touches each segment at the foot of the perpendicular from the center
+pub fn fillet(fst: &Segment, snd: &Segment, radius: f64) -> Result<Fillet, CornerError> {
+    let turn = turn(fst, snd)?;
+    if radius <= 0.0 {
+        return Err(CornerError::TooLarge);
+    }
+    // the inside of a corner turning left is on the left side, whose offset is negative
+    let (fst_offsets, snd_offsets) = (fst.offset(-turn * radius), snd.offset(-turn * radius));
+    let corner = fst.end();
+    let mut centers = Vec::new();
+    for fst_offset in &fst_offsets {
+        for snd_offset in &snd_offsets {
+            if let Intersections::Points(points) = fst_offset.shape().intersect(snd_offset.shape())
+            {
+                centers.extend(points.into_iter().map(|each| each.point));
+            }
+        }
+    }
+    let center = centers
+        .into_iter()
+        .min_by(|a, b| a.distance(corner).partial_cmp(&b.distance(corner)).unwrap())
+        .ok_or(CornerError::TooLarge)?;
+    let (t1, t2) = (fst.shape().project(center).t, snd.shape().project(center).t);
+    let fst = fst.sub_segment(fst.t_range().0, t1);
+    let snd = snd.sub_segment(t2, snd.t_range().1);
+    let (v1, v2) = (fst.end() - center, snd.start() - center);
+    let sweep_angle = (v1.x * v2.y - v1.y * v2.x).atan2(v1.x * v2.x + v1.y * v2.y);
+    Ok(Fillet {
+        arc: Arc::new(
+            center,
+            radius,
+            v1.y.atan2(v1.x).to_degrees(),
+            sweep_angle.to_degrees(),
+        ),
+        fst,
+        snd,
+    })
+}
+
+/// 1 if the second segment turns left from the first one, or -1 if right
+fn turn(fst: &Segment, snd: &Segment) -> Result<f64, CornerError> {
+    if fst.end().distance(snd.start()) > TOLERANCE {
+        return Err(CornerError::NotAdjacent);
+    }
+    let (d1, d2) = (fst.tangent(fst.t_range().1), snd.tangent(snd.t_range().0));
+    let sin = d1.x * d2.y - d1.y * d2.x;
+    if sin.abs() < TANGENT_TOLERANCE {
+        Err(CornerError::NoCorner)
+    } else if sin > 0.0 {
+        Ok(1.0)
+    } else {
+        Ok(-1.0)
+    }
+}
+
+#[cfg(test)]
+mod test_corner {
+    use crate::{
+        contour::Segment,
+        corner::{chamfer, fillet, CornerError},
+        shapes::{
+            bezier::{Bezier, TOLERANCE},
+            geometry::Geometry,
+            line::Line,
+            point::Point,
+            Shape,
+        },
+    };
+
+    fn line(x0: f64, y0: f64, x1: f64, y1: f64) -> Segment {
+        Segment::new(Shape::Line(Line::new(
+            Point::new(x0, y0),
+            Point::new(x1, y1),
+        )))
+    }
+
+    fn assert_near(p: Point, q: Point) {
+        assert!(
+            p.distance(q) < 1e-9,
+            "({}, {}) != ({}, {})",
+            p.x,
+            p.y,
+            q.x,
+            q.y
+        );
+    }
+
+    /// the arc starts where the first segment ends and ends where the second one starts, in their directions
+    fn assert_tangent(fst: &Segment, arc: &Shape, snd: &Segment) {
+        let (from, to) = arc.t_range();
+        assert_near(arc.start(), fst.end());
+        assert_near(arc.end(), snd.start());
+        assert_near(arc.tangent(from), fst.tangent(fst.t_range().1));
+        assert_near(arc.tangent(to), snd.tangent(snd.t_range().0));
+    }
+
+    #[test]
+    fn test_fillet_square() {
+        let (fst, snd) = (line(0.0, 0.0, 10.0, 0.0), line(10.0, 0.0, 10.0, 10.0));
+        let left = fillet(&fst, &snd, 2.0).unwrap();
+        assert_near(left.arc.origin, Point::new(8.0, 2.0));
+        assert!((left.arc.r - 2.0).abs() < 1e-12);
+        assert!((left.arc.sweep_angle - 90.0).abs() < 1e-9);
+        assert_near(left.fst.end(), Point::new(8.0, 0.0));
+        assert_near(left.snd.start(), Point::new(10.0, 2.0));
+        assert_tangent(&left.fst, &Shape::Arc(left.arc), &left.snd);
+        // turning right
+        let right = fillet(&fst, &line(10.0, 0.0, 10.0, -10.0), 2.0).unwrap();
+        assert_near(right.arc.origin, Point::new(8.0, -2.0));
+        assert!((right.arc.sweep_angle + 90.0).abs() < 1e-9);
+        assert_tangent(&right.fst, &Shape::Arc(right.arc), &right.snd);
+    }
+
+    #[test]
+    fn test_fillet_bezier() {
+        let fst = Segment::new(Shape::Bezier(Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(5.0, 4.0),
+            Point::new(10.0, 0.0),
+        ])));
+        let snd = line(10.0, 0.0, 10.0, 10.0);
+        let rounded = fillet(&fst, &snd, 1.0).unwrap();
+        let arc = Shape::Arc(rounded.arc);
+        // the offset of the curve is approximated within the tolerance, and so are the center and the joints
+        let center = rounded.arc.origin;
+        assert!((fst.shape().project(center).distance - 1.0).abs() < TOLERANCE);
+        assert!((snd.shape().project(center).distance - 1.0).abs() < TOLERANCE);
+        assert!(rounded.fst.end().distance(arc.start()) < TOLERANCE);
+        assert!(rounded.snd.start().distance(arc.end()) < TOLERANCE);
+        let cross = |d1: Point, d2: Point| d1.x * d2.y - d1.y * d2.x;
+        let (d1, d2) = (
+            rounded.fst.tangent(rounded.fst.t_range().1),
+            arc.tangent(arc.t_range().0),
+        );
+        assert!(cross(d1, d2).abs() < TOLERANCE && d1.x * d2.x + d1.y * d2.y > 0.0);
+        assert_near(arc.tangent(arc.t_range().1), Point::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_chamfer() {
+        let (fst, snd) = (line(0.0, 0.0, 10.0, 0.0), line(10.0, 0.0, 10.0, 10.0));
+        let cut = chamfer(&fst, &snd, 3.0).unwrap();
+        assert_near(cut.line.origin, Point::new(7.0, 0.0));
+        assert_near(cut.line.end, Point::new(10.0, 3.0));
+        assert_near(cut.fst.end(), cut.line.origin);
+        assert_near(cut.snd.start(), cut.line.end);
+        assert_near(cut.snd.end(), Point::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn test_corner_error() {
+        let (fst, snd) = (line(0.0, 0.0, 10.0, 0.0), line(10.0, 0.0, 10.0, 10.0));
+        assert_eq!(fillet(&fst, &snd, 20.0).err(), Some(CornerError::TooLarge));
+        assert_eq!(fillet(&fst, &snd, 0.0).err(), Some(CornerError::TooLarge));
+        assert_eq!(chamfer(&fst, &snd, 10.0).err(), Some(CornerError::TooLarge));
+        let straight = line(10.0, 0.0, 20.0, 0.0);
+        assert_eq!(
+            fillet(&fst, &straight, 1.0).err(),
+            Some(CornerError::NoCorner)
+        );
+        let back = line(10.0, 0.0, 5.0, 0.0);
+        assert_eq!(chamfer(&fst, &back, 1.0).err(), Some(CornerError::NoCorner));
+        let apart = line(10.0, 1.0, 10.0, 10.0);
+        assert_eq!(
+            fillet(&fst, &apart, 1.0).err(),
+            Some(CornerError::NotAdjacent)
+        );
+        assert_eq!(
+            chamfer(&fst, &apart, 1.0).err(),
+            Some(CornerError::NotAdjacent)
+        );
+    }
+}
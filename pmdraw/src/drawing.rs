@@ -1,11 +1,26 @@
-use std::f64::consts::PI;
-
 // #[cfg(not(target_arch = "wasm32"))]
 // use pmrender::show_lines;
 
 use pmrender::show_lines;
 
-use crate::shapes::{bezier::Bezier, circle::Circle, line::Line, point::Point, Shape};
+use crate::{
+    flatten::TOLERANCE,
+    index::GridIndex,
+    shapes::{
+        arc::Arc,
+        bezier::Bezier,
+        circle::Circle,
+        ellipse::EllipticalArc,
+        geometry::{BoundingBox, Geometry},
+        line::Line,
+        point::Point,
+        spline::Spline,
+        Shape,
+    },
+};
+
+/// length of a side of each cell of the index of shapes in centimeters, which is about the size of a pattern piece
+const INDEX_CELL_SIZE: f64 = 10.0;
 
 #[derive(Clone)]
 pub struct Drawing {
@@ -13,54 +28,48 @@ pub struct Drawing {
     pub width: f64,
     /// canvas height in centimeters
     pub height: f64,
-    pub shapes: Vec<Shape>,
+    shapes: Vec<Shape>,
+    /// bounding boxes of shapes, where the id of each box is the index of the shape
+    index: GridIndex,
     vertices: Vec<(f32, f32)>,
 }
 
 impl Drawing {
+    pub fn arc(&mut self, a: &Arc) {
+        self.arc_with_tolerance(a, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the arc and lines to draw it in centimeters
+    pub fn arc_with_tolerance(&mut self, a: &Arc, tolerance: f64) {
+        self.shape_with_tolerance(Shape::Arc(*a), tolerance);
+    }
+
     pub fn bezier(&mut self, b: &Bezier) {
-        self.bezier_with_precision(b, 100)
-    }
-    pub fn bezier_with_precision(&mut self, b: &Bezier, precision: u32) {
-        self.shapes.push(Shape::Bezier(b.clone()));
-        let t_range = b.t_range();
-        let mut t = t_range.from;
-        let dt = t_range.to / precision as f64;
-        while t <= t_range.to {
-            self.line_no_store(Line::new(
-                b.point_at(t),
-                if t + dt < t_range.to {
-                    b.point_at(t + dt)
-                } else {
-                    b.point_at(t_range.to)
-                },
-            ));
-            t += dt;
-        }
+        self.bezier_with_tolerance(b, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the curve and lines to draw it in centimeters
+    pub fn bezier_with_tolerance(&mut self, b: &Bezier, tolerance: f64) {
+        self.shape_with_tolerance(Shape::Bezier(b.clone()), tolerance);
+    }
+
+    /// Box of all the shapes, or none if nothing is drawn
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.index.bounding_box()
     }
 
     pub fn circle(&mut self, origin: Point, r: f64) {
-        self.circle_with_precision(origin, r, 100)
-    }
-    pub fn circle_with_precision(&mut self, origin: Point, r: f64, precision: u32) {
-        let c = Circle::new(origin, r);
-        self.shapes.push(Shape::Circle(c));
-        let dt = 1.0 / (precision - 1) as f64;
-        let mut p0 = origin.to(r, 0.0);
-        for i in 1..precision {
-            let p1 = match i as f64 * dt {
-                t @ _ => {
-                    if t >= 1.0 {
-                        origin.to(r, 0.0)
-                    } else {
-                        let theta = 2.0 * PI * t;
-                        origin.to(r * theta.cos(), r * theta.sin())
-                    }
-                }
-            };
-            self.line(Line::new(p0, p1));
-            p0 = p1;
-        }
+        self.circle_with_tolerance(origin, r, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the circle and lines to draw it in centimeters
+    pub fn circle_with_tolerance(&mut self, origin: Point, r: f64, tolerance: f64) {
+        self.shape_with_tolerance(Shape::Circle(Circle::new(origin, r)), tolerance);
+    }
+
+    pub fn elliptical_arc(&mut self, e: &EllipticalArc) {
+        self.elliptical_arc_with_tolerance(e, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the arc and lines to draw it in centimeters
+    pub fn elliptical_arc_with_tolerance(&mut self, e: &EllipticalArc, tolerance: f64) {
+        self.shape_with_tolerance(Shape::EllipticalArc(*e), tolerance);
     }
 
     pub fn line_from_point(&mut self, origin_x: f64, origin_y: f64, end_x: f64, end_y: f64) {
@@ -78,16 +87,21 @@ impl Drawing {
     fn line_no_store(&mut self, l: Line) {
         self.line_with_store(l, false);
     }
+    fn polyline_no_store(&mut self, points: &[Point]) {
+        for points in points.windows(2) {
+            self.line_no_store(Line::new(points[0], points[1]));
+        }
+    }
     fn line_with_store(&mut self, l: Line, stores_shape: bool) {
         if stores_shape {
-            self.shapes.push(Shape::Line(l.clone()));
+            self.store(Shape::Line(l.clone()));
         }
         self.vertices.push((l.origin.x as f32, l.origin.y as f32));
         self.vertices.push((l.end.x as f32, l.end.y as f32));
     }
     fn line_with_store_borrow(&mut self, l: &Line, stores_shape: bool) {
         if stores_shape {
-            self.shapes.push(Shape::Line(l.clone()));
+            self.store(Shape::Line(l.clone()));
         }
         self.vertices.push((l.origin.x as f32, l.origin.y as f32));
         self.vertices.push((l.end.x as f32, l.end.y as f32));
@@ -95,18 +109,66 @@ impl Drawing {
 
     pub fn new(width: f64, height: f64) -> Drawing {
         let shapes = Vec::new();
+        let index = GridIndex::new(INDEX_CELL_SIZE);
         let vertices = Vec::new();
         Drawing {
             width,
             height,
             shapes,
+            index,
             vertices,
         }
     }
 
     pub fn point(&mut self, p: Point) {
-        self.shapes.push(Shape::Point(p));
-        self.circle_with_precision(p, 0.3, 20);
+        self.store(Shape::Point(p));
+        self.circle_with_tolerance(p, 0.3, TOLERANCE);
+    }
+
+    /// Draw any shape, where a point is marked by a small circle
+    pub fn shape(&mut self, shape: Shape) {
+        self.shape_with_tolerance(shape, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the shape and lines to draw it in centimeters
+    pub fn shape_with_tolerance(&mut self, shape: Shape, tolerance: f64) {
+        match shape {
+            Shape::Point(p) => self.point(p),
+            shape => {
+                self.polyline_no_store(&shape.flatten(tolerance));
+                self.store(shape);
+            }
+        }
+    }
+
+    pub fn shapes(&self) -> &Vec<Shape> {
+        &self.shapes
+    }
+
+    /// Shapes whose bounding boxes overlap the region, in the order they are drawn
+    pub fn shapes_in(&self, region: &BoundingBox) -> Vec<&Shape> {
+        self.index
+            .overlapping(region)
+            .into_iter()
+            .map(|i| &self.shapes[i])
+            .collect()
+    }
+
+    /// Shapes within the distance from p, in the order they are drawn
+    pub fn shapes_near(&self, p: Point, distance: f64) -> Vec<&Shape> {
+        self.index
+            .near(p, distance)
+            .into_iter()
+            .map(|i| &self.shapes[i])
+            .filter(|shape| shape.project(p).distance <= distance)
+            .collect()
+    }
+
+    pub fn spline(&mut self, s: &Spline) {
+        self.spline_with_tolerance(s, TOLERANCE)
+    }
+    /// - tolerance - distance allowed between the curve and lines to draw it in centimeters
+    pub fn spline_with_tolerance(&mut self, s: &Spline, tolerance: f64) {
+        self.shape_with_tolerance(Shape::Spline(s.clone()), tolerance);
     }
 
     pub fn show(&self, _window_width: u32, _window_height: u32) {
@@ -136,4 +198,61 @@ impl Drawing {
             )
         };
     }
+
+    /// Keep the shape and its bounding box in the index
+    fn store(&mut self, shape: Shape) {
+        self.index.insert(shape.bounding_box());
+        self.shapes.push(shape);
+    }
+}
+
+#[cfg(test)]
+mod test_drawing {
+    use crate::{
+        drawing::Drawing,
+        shapes::{geometry::BoundingBox, line::Line, point::Point, Shape},
+    };
+
+    /// x of the origin of each line among shapes
+    fn origins_x(shapes: Vec<&Shape>) -> Vec<f64> {
+        shapes
+            .into_iter()
+            .filter_map(|shape| match shape {
+                Shape::Line(l) => Some(l.origin.x),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_shapes_in() {
+        let mut drawing = Drawing::new(100.0, 100.0);
+        for x in [50.0, 0.0, 20.0, 5.0].iter() {
+            drawing.line(Line::new(Point::new(*x, 0.0), Point::new(*x + 3.0, 4.0)));
+        }
+        let region = BoundingBox {
+            min: Point::new(2.0, 1.0),
+            max: Point::new(21.0, 2.0),
+        };
+        // in the order they are drawn
+        assert_eq!(origins_x(drawing.shapes_in(&region)), vec![0.0, 20.0, 5.0]);
+        let b = drawing.bounding_box().unwrap();
+        assert!(b.min == Point::new(0.0, 0.0) && b.max == Point::new(53.0, 4.0));
+    }
+
+    #[test]
+    fn test_shapes_near() {
+        let mut drawing = Drawing::new(100.0, 100.0);
+        drawing.line(Line::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0)));
+        drawing.circle(Point::new(30.0, 0.0), 5.0);
+        // inside the bounding boxes of both, but far from the circle
+        let p = Point::new(30.0, 1.0);
+        assert!(drawing.shapes_near(p, 1.0).is_empty());
+        assert_eq!(drawing.shapes_near(Point::new(5.0, 4.0), 1.0).len(), 1);
+        let near = drawing.shapes_near(Point::new(30.0, 4.5), 1.0);
+        assert!(near.len() == 1 && matches!(near[0], Shape::Circle(_)));
+        // the nearest point of the line is within the distance, though its box is at the corner
+        let near = drawing.shapes_near(Point::new(8.0, 7.0), 0.8);
+        assert!(near.len() == 1 && matches!(near[0], Shape::Line(_)));
+    }
 }
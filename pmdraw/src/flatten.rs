@@ -0,0 +1,243 @@
+use crate::shapes::{
+    arc::Arc, bezier::Bezier, circle::Circle, ellipse::EllipticalArc, line::Line, point::Point,
+    spline::Spline,
+};
+
+/// default distance between a curve and its chords in centimeters
+pub const TOLERANCE: f64 = 0.01;
+/// curves are divided into 2^MIN_DEPTH pieces at least, not to miss a curve whose samples happen to be on a chord
+const MIN_DEPTH: usize = 2;
+/// give up halving pieces after this number of halvings
+const MAX_DEPTH: usize = 16;
+/// a circle is drawn by this number of chords at least
+const MIN_CHORDS: usize = 8;
+
+/// Points along the arc from the start to the end at even angles
+///
+/// the number of chords is chosen in the same way as `flatten_circle`
+pub fn flatten_arc(a: &Arc, tolerance: f64) -> Vec<Point> {
+    let count_chords = count_chords(a.r, a.sweep_angle, tolerance);
+    (0..=count_chords)
+        .map(|i| a.point_at(a.start_angle + a.sweep_angle * i as f64 / count_chords as f64))
+        .collect()
+}
+
+/// Points along the curve in its range, halving pieces until their chords are within the tolerance
+pub fn flatten_bezier(b: &Bezier, tolerance: f64) -> Vec<Point> {
+    let range = b.t_range();
+    let origin = b.point_at(range.from);
+    let mut points = vec![origin];
+    subdivide(
+        b,
+        (range.from, origin),
+        (range.to, b.point_at(range.to)),
+        tolerance,
+        0,
+        &mut points,
+    );
+    points
+}
+
+/// Points around the circle counterclockwise from the angle 0, where the last point is the first one
+///
+/// the number of chords is chosen so that the gap between each chord and the arc is within the tolerance
+pub fn flatten_circle(c: &Circle, tolerance: f64) -> Vec<Point> {
+    let count_chords = count_chords(c.r, 360.0, tolerance);
+    let mut points = (0..count_chords)
+        .map(|i| c.point_at(360.0 * i as f64 / count_chords as f64))
+        .collect::<Vec<Point>>();
+    points.push(points[0]);
+    points
+}
+
+/// Points along the elliptical arc from the start to the end
+///
+/// the arc is approximated by cubic curves within half of the tolerance, which are flattened within the other half
+pub fn flatten_elliptical_arc(e: &EllipticalArc, tolerance: f64) -> Vec<Point> {
+    let mut points = vec![e.start()];
+    for b in e.to_beziers(tolerance / 2.0) {
+        points.extend(flatten_bezier(&b, tolerance / 2.0).into_iter().skip(1));
+    }
+    points
+}
+
+/// Points along all the segments, where joints of segments appear once
+pub fn flatten_spline(s: &Spline, tolerance: f64) -> Vec<Point> {
+    let mut points = vec![s.origin()];
+    for b in s.segments() {
+        points.extend(flatten_bezier(b, tolerance).into_iter().skip(1));
+    }
+    points
+}
+
+/// Number of chords of an arc, where the gap between each chord and the arc is within the tolerance
+///
+/// a whole circle has MIN_CHORDS chords at least, and a part of it has the same proportion of them
+fn count_chords(r: f64, sweep_angle: f64, tolerance: f64) -> usize {
+    let sweep = sweep_angle.abs().to_radians();
+    let at_least = (MIN_CHORDS as f64 * sweep_angle.abs() / 360.0)
+        .ceil()
+        .max(1.0) as usize;
+    // the gap is r (1 - cos(theta / 2)) for the chord of angle theta
+    if tolerance < r {
+        let theta = 2.0 * (1.0 - tolerance / r).acos();
+        ((sweep / theta).ceil() as usize).max(at_least)
+    } else {
+        at_least
+    }
+}
+
+/// Push points from t0 (exclusive) to t1 (inclusive), halving the piece while it is not flat enough
+fn subdivide(
+    b: &Bezier,
+    (t0, p0): (f64, Point),
+    (t1, p1): (f64, Point),
+    tolerance: f64,
+    depth: usize,
+    points: &mut Vec<Point>,
+) {
+    let chord = Line::new(p0, p1);
+    let middle = (t0 + t1) / 2.0;
+    let p_middle = b.point_at(middle);
+    let error = [
+        b.point_at((t0 + middle) / 2.0),
+        p_middle,
+        b.point_at((middle + t1) / 2.0),
+    ]
+    .iter()
+    .map(|p| chord.project(*p).distance)
+    .fold(0.0, f64::max);
+    if depth >= MAX_DEPTH || (depth >= MIN_DEPTH && error <= tolerance) {
+        points.push(p1);
+    } else {
+        subdivide(
+            b,
+            (t0, p0),
+            (middle, p_middle),
+            tolerance,
+            depth + 1,
+            points,
+        );
+        subdivide(
+            b,
+            (middle, p_middle),
+            (t1, p1),
+            tolerance,
+            depth + 1,
+            points,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_flatten {
+    use crate::{
+        flatten::{flatten_bezier, flatten_circle, flatten_spline},
+        shapes::{
+            bezier::Bezier,
+            circle::Circle,
+            point::Point,
+            spline::{Spline, SplineKind},
+        },
+    };
+
+    /// largest distance from points to the polyline
+    fn max_error(polyline: &[Point], points: &[Point]) -> f64 {
+        points
+            .iter()
+            .map(|p| {
+                polyline
+                    .windows(2)
+                    .map(|w| w[0].line_to(w[1]).project(*p).distance)
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .fold(0.0, f64::max)
+    }
+
+    fn large_arch() -> Bezier {
+        Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(10.0, 30.0),
+            Point::new(40.0, 30.0),
+            Point::new(50.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn test_flatten_bezier() {
+        let b = large_arch();
+        let samples = (0..=1000)
+            .map(|i| b.point_at(i as f64 / 1000.0))
+            .collect::<Vec<Point>>();
+        for tolerance in [0.1, 0.01, 0.0001].iter() {
+            let points = flatten_bezier(&b, *tolerance);
+            assert!(points[0] == b.origin() && points[points.len() - 1] == b.end());
+            assert!(max_error(&points, &samples) <= *tolerance);
+        }
+        // finer tolerance takes more points, about twice by a quarter of the tolerance
+        let (coarse, fine) = (
+            flatten_bezier(&b, 0.04).len() as f64,
+            flatten_bezier(&b, 0.01).len() as f64,
+        );
+        assert!(1.5 * coarse < fine && fine < 2.5 * coarse);
+    }
+
+    #[test]
+    fn test_flatten_bezier_in_range() {
+        // the range starts above 0
+        let mut b = large_arch();
+        b.set_range(b.point_at(0.5), b.point_at(1.0));
+        let tolerance = 0.0001;
+        let points = flatten_bezier(&b, tolerance);
+        assert!(points[0].distance(b.point_at(0.5)) < 1e-6);
+        assert!(points[points.len() - 1].distance(b.end()) < 1e-6);
+        assert!(points.iter().all(|p| p.x >= 25.0 - 1e-6));
+        let samples = (0..=1000)
+            .map(|i| b.point_at(0.5 + 0.5 * i as f64 / 1000.0))
+            .collect::<Vec<Point>>();
+        assert!(max_error(&points, &samples) <= tolerance);
+        // as many points as the other half, which is symmetric
+        let mut fst = large_arch();
+        fst.set_range(fst.point_at(0.0), fst.point_at(0.5));
+        assert_eq!(points.len(), flatten_bezier(&fst, tolerance).len());
+    }
+
+    #[test]
+    fn test_flatten_circle() {
+        let c = Circle::new(Point::new(1.0, 1.0), 10.0);
+        let tolerance = 0.01;
+        let points = flatten_circle(&c, tolerance);
+        assert!(points[0] == points[points.len() - 1]);
+        for w in points.windows(2) {
+            assert!((w[0].distance(c.origin) - 10.0).abs() < 1e-9);
+            assert!(10.0 - w[0].middle(w[1]).distance(c.origin) <= tolerance);
+        }
+        // a tiny circle has chords at least
+        assert_eq!(
+            flatten_circle(&Circle::new(c.origin, 0.001), tolerance).len(),
+            9
+        );
+    }
+
+    #[test]
+    fn test_flatten_spline() {
+        let s = Spline::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(10.0, 10.0),
+                Point::new(20.0, 0.0),
+                Point::new(30.0, 10.0),
+            ],
+            SplineKind::Natural,
+        );
+        let points = flatten_spline(&s, 0.01);
+        // joints of segments appear once
+        assert!(points.windows(2).all(|w| w[0].distance(w[1]) > 1e-9));
+        let count = s
+            .segments()
+            .iter()
+            .map(|b| flatten_bezier(b, 0.01).len() - 1)
+            .sum::<usize>();
+        assert_eq!(points.len(), count + 1);
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::shapes::{geometry::BoundingBox, point::Point};
+
+/// Uniform grid of square cells, where each cell holds the ids of boxes overlapping it
+///
+/// looking for boxes in a region visits only the cells overlapping the region,
+/// thus it is cheap as long as each box spans a few cells
+#[derive(Clone)]
+pub struct GridIndex {
+    /// length of a side of each cell in centimeters
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    /// bounding box of each id
+    boxes: Vec<BoundingBox>,
+}
+
+impl GridIndex {
+    /// Box of all the boxes in the index, or none if it is empty
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.boxes
+            .iter()
+            .fold(None, |union: Option<BoundingBox>, each| match union {
+                Some(union) => Some(union.union(each)),
+                None => Some(*each),
+            })
+    }
+
+    /// Add a box and get its id, which is the number of boxes added before it
+    pub fn insert(&mut self, bounding_box: BoundingBox) -> usize {
+        let id = self.boxes.len();
+        self.boxes.push(bounding_box);
+        let (i_min, i_max) = self.cell_range(bounding_box.min.x, bounding_box.max.x);
+        let (j_min, j_max) = self.cell_range(bounding_box.min.y, bounding_box.max.y);
+        for i in i_min..=i_max {
+            for j in j_min..=j_max {
+                self.cells.entry((i, j)).or_default().push(id);
+            }
+        }
+        id
+    }
+
+    /// Ids of boxes within the distance from p in ascending order
+    pub fn near(&self, p: Point, distance: f64) -> Vec<usize> {
+        let region = BoundingBox {
+            min: p.to(-distance, -distance),
+            max: p.to(distance, distance),
+        };
+        self.overlapping(&region)
+            .into_iter()
+            .filter(|id| {
+                let b = self.boxes[*id];
+                let dx = (b.min.x - p.x).max(p.x - b.max.x).max(0.0);
+                let dy = (b.min.y - p.y).max(p.y - b.max.y).max(0.0);
+                dx * dx + dy * dy <= distance * distance
+            })
+            .collect()
+    }
+
+    /// - cell_size - length of a side of each cell in centimeters, which is about the size of boxes to be added
+    pub fn new(cell_size: f64) -> GridIndex {
+        assert!(cell_size > 0.0, "Cell size must be positive");
+        GridIndex {
+            cell_size,
+            cells: HashMap::new(),
+            boxes: Vec::new(),
+        }
+    }
+
+    /// Ids of boxes overlapping the region, including the ones touching its edges, in ascending order
+    pub fn overlapping(&self, region: &BoundingBox) -> Vec<usize> {
+        let ((i_min, i_max), (j_min, j_max)) = (
+            self.cell_range(region.min.x, region.max.x),
+            self.cell_range(region.min.y, region.max.y),
+        );
+        let count_cells = (i_max as i128 - i_min as i128 + 1) * (j_max as i128 - j_min as i128 + 1);
+        let candidates = if count_cells <= self.cells.len() as i128 {
+            (i_min..=i_max)
+                .flat_map(|i| (j_min..=j_max).map(move |j| (i, j)))
+                .filter_map(|cell| self.cells.get(&cell))
+                .flatten()
+                .copied()
+                .collect::<Vec<usize>>()
+        } else {
+            // a region larger than the occupied cells is cheaper to check against each of them
+            self.cells
+                .iter()
+                .filter(|((i, j), _)| i_min <= *i && *i <= i_max && j_min <= *j && *j <= j_max)
+                .flat_map(|(_, ids)| ids.iter().copied())
+                .collect()
+        };
+        let mut ids = candidates
+            .into_iter()
+            .filter(|id| self.boxes[*id].overlaps(region))
+            .collect::<Vec<usize>>();
+        // a box spanning several cells is found in each of them
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Indices of the first and the last cells covering from min to max along an axis
+    fn cell_range(&self, min: f64, max: f64) -> (i64, i64) {
+        (
+            (min / self.cell_size).floor() as i64,
+            (max / self.cell_size).floor() as i64,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_index {
+    use crate::{
+        index::GridIndex,
+        shapes::{geometry::BoundingBox, point::Point},
+    };
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(x0, y0),
+            max: Point::new(x1, y1),
+        }
+    }
+
+    /// ids of boxes overlapping the region, checking every box
+    fn overlapping_all(boxes: &[BoundingBox], region: &BoundingBox) -> Vec<usize> {
+        (0..boxes.len())
+            .filter(|id| boxes[*id].overlaps(region))
+            .collect()
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let mut index = GridIndex::new(10.0);
+        let boxes = [
+            rect(1.0, 1.0, 3.0, 3.0),
+            // spanning many cells, including negative ones
+            rect(-25.0, -5.0, 35.0, 5.0),
+            rect(40.0, 40.0, 42.0, 45.0),
+            rect(-12.0, 18.0, -11.0, 19.0),
+        ];
+        for (id, b) in boxes.iter().enumerate() {
+            assert_eq!(index.insert(*b), id);
+        }
+        for region in [
+            rect(2.0, 2.0, 2.5, 2.5),
+            rect(-30.0, -30.0, 0.0, 0.0),
+            rect(0.0, 0.0, 50.0, 50.0),
+            rect(-15.0, 10.0, -11.5, 20.0),
+            rect(100.0, 100.0, 101.0, 101.0),
+            // larger than the occupied cells
+            rect(-1000.0, -1000.0, 1000.0, 1000.0),
+        ]
+        .iter()
+        {
+            assert_eq!(index.overlapping(region), overlapping_all(&boxes, region));
+        }
+        // a box spanning several cells is found once
+        assert_eq!(index.overlapping(&rect(-30.0, -1.0, 40.0, 0.5)), vec![1]);
+        // boxes touching the edges of the region are included
+        assert_eq!(index.overlapping(&rect(-11.0, 19.0, -10.0, 20.0)), vec![3]);
+        assert_eq!(index.overlapping(&rect(42.0, 45.0, 42.0, 45.0)), vec![2]);
+    }
+
+    #[test]
+    fn test_near() {
+        let mut index = GridIndex::new(10.0);
+        index.insert(rect(0.0, 0.0, 2.0, 2.0));
+        index.insert(rect(5.0, 0.0, 6.0, 1.0));
+        // the corner of the first box is within the square around p, but farther than the distance
+        assert_eq!(index.near(Point::new(3.0, 3.0), 1.2), Vec::<usize>::new());
+        assert_eq!(index.near(Point::new(3.0, 3.0), 1.5), vec![0]);
+        // the distance to a box is 0 inside it
+        assert_eq!(index.near(Point::new(1.0, 1.0), 0.0), vec![0]);
+        assert_eq!(index.near(Point::new(3.5, 0.5), 1.5), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut index = GridIndex::new(10.0);
+        assert!(index.bounding_box().is_none());
+        index.insert(rect(1.0, 2.0, 3.0, 4.0));
+        index.insert(rect(-5.0, 3.0, 0.0, 30.0));
+        let b = index.bounding_box().unwrap();
+        assert!(b.min == Point::new(-5.0, 2.0) && b.max == Point::new(3.0, 30.0));
+    }
+}
@@ -1,5 +1,12 @@
 #[macro_use]
 extern crate impl_ops;
 
+pub mod boolean;
+pub mod constraint;
+pub mod construction;
+pub mod contour;
+pub mod corner;
 pub mod drawing;
+pub mod flatten;
+pub mod index;
 pub mod shapes;
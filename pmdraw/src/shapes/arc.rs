@@ -0,0 +1,358 @@
+use pmmath::transform::Transform2;
+
+use super::{
+    bezier::{Bezier, TOLERANCE},
+    circle::Circle,
+    ellipse::EllipticalArc,
+    lengths_every,
+    line::Line,
+    point::Point,
+    projection::Projection,
+};
+
+/// Part of a circle from the start angle, going counterclockwise if the sweep angle is positive and clockwise if negative
+///
+/// the parameter t of an arc is the angle in degrees between the start angle and start angle + sweep angle
+#[derive(Copy, Clone)]
+pub struct Arc {
+    /// center of the circle
+    pub origin: Point,
+    pub r: f64,
+    pub start_angle: f64,
+    pub sweep_angle: f64,
+}
+
+impl Arc {
+    /// The circle which the arc is on
+    pub fn circle(&self) -> Circle {
+        Circle::new(self.origin, self.r)
+    }
+
+    pub fn end(&self) -> Point {
+        self.point_at(self.start_angle + self.sweep_angle)
+    }
+
+    pub fn len(&self) -> f64 {
+        self.r * self.sweep_angle.abs().to_radians()
+    }
+
+    pub fn mirror(&self, mirror_line: Line) -> Arc {
+        let mut arc = *self;
+        arc.transform(&Transform2::mirror(
+            mirror_line.origin.x,
+            mirror_line.origin.y,
+            mirror_line.end.x,
+            mirror_line.end.y,
+        ));
+        arc
+    }
+
+    /// - start_angle - angle of the start point in degrees
+    /// - sweep_angle - angle from the start point to the end point in degrees, which is counterclockwise if positive
+    pub fn new(origin: Point, r: f64, start_angle: f64, sweep_angle: f64) -> Arc {
+        Arc {
+            origin,
+            r,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    /// Arc at the distance from the arc, which is on the same circle with a different radius
+    ///
+    /// positive distance is on the right side of the direction of the arc like `Bezier::offset`,
+    /// and negative is on the left. none if the arc shrinks into the center
+    pub fn offset(&self, distance: f64) -> Option<Arc> {
+        // the right side of a counterclockwise arc is outside
+        let r = self.r + self.direction() * distance;
+        if r > 0.0 {
+            Some(Arc { r, ..*self })
+        } else {
+            None
+        }
+    }
+
+    pub fn point_at(&self, angle_degree: f64) -> Point {
+        self.circle().point_at(angle_degree)
+    }
+
+    /// Point at the length along the arc from the start
+    pub fn point_at_length(&self, length: f64) -> Point {
+        self.point_at(self.t_at_length(length))
+    }
+
+    /// Points at even intervals of length along the arc from the start
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(self.len(), interval)
+            .into_iter()
+            .map(|length| self.point_at_length(length))
+            .collect()
+    }
+
+    /// Nearest point on the arc to p, where t is the angle in degrees
+    pub fn project(&self, p: Point) -> Projection {
+        let mut candidates = vec![self.start_angle, self.start_angle + self.sweep_angle];
+        let v = p - self.origin;
+        if v.x != 0.0 || v.y != 0.0 {
+            let angle = v.y.atan2(v.x).to_degrees();
+            candidates.extend(angle_in_sweep(
+                angle,
+                self.start_angle,
+                self.sweep_angle,
+                0.0,
+            ));
+        }
+        Projection::nearest(
+            candidates
+                .into_iter()
+                .map(|t| Projection::new(t, self.point_at(t), p)),
+        )
+        .expect("Edges of the arc are always candidates")
+    }
+
+    /// Swap the start and the end
+    pub fn reverse(&mut self) {
+        self.start_angle += self.sweep_angle;
+        self.sweep_angle = -self.sweep_angle;
+    }
+
+    /// Rotate around point
+    pub fn rotate(&mut self, angle_degree: f64, around: Point) {
+        self.transform(&Transform2::rotate_around(angle_degree, around.x, around.y));
+    }
+
+    /// Split the arc at the nearest point to p
+    pub fn split(&self, p: Point) -> Split {
+        self.split_at_t(self.project(p).t)
+    }
+
+    /// Split the arc at the length from the start
+    pub fn split_at_length(&self, length: f64) -> Split {
+        self.split_at_t(self.t_at_length(length))
+    }
+
+    /// Split the arc at the angle in degrees
+    pub fn split_at_t(&self, angle_degree: f64) -> Split {
+        let end_angle = self.start_angle + self.sweep_angle;
+        Split {
+            fst: Arc::new(
+                self.origin,
+                self.r,
+                self.start_angle,
+                angle_degree - self.start_angle,
+            ),
+            snd: Arc::new(self.origin, self.r, angle_degree, end_angle - angle_degree),
+        }
+    }
+
+    pub fn start(&self) -> Point {
+        self.point_at(self.start_angle)
+    }
+
+    /// Angle in degrees of the point at the length along the arc from the start
+    ///
+    /// negative length is the start, and panics if the length exceeds the arc like `EllipticalArc::t_at_length`
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        assert!(length <= self.len() + TOLERANCE, "Length is out of the arc");
+        let length = length.max(0.0).min(self.len());
+        self.start_angle + self.direction() * (length / self.r).to_degrees()
+    }
+
+    /// Unit tangent at the angle in the direction of the arc
+    pub fn tangent(&self, angle_degree: f64) -> Point {
+        let theta = angle_degree.to_radians();
+        self.direction() * Point::new(-theta.sin(), theta.cos())
+    }
+
+    /// Unit tangent at the length along the arc from the start
+    pub fn tangent_at_length(&self, length: f64) -> Point {
+        self.tangent(self.t_at_length(length))
+    }
+
+    pub fn to(&mut self, dx: f64, dy: f64) {
+        self.origin = self.origin.to(dx, dy);
+    }
+
+    /// Cubic curves which approximate the arc within the tolerance
+    ///
+    /// each curve spans an equal angle, which is at most 90 degrees
+    pub fn to_beziers(&self, tolerance: f64) -> Vec<Bezier> {
+        let count = count_cubic_pieces(self.r, self.sweep_angle, tolerance);
+        let step = self.sweep_angle / count as f64;
+        (0..count)
+            .map(|i| {
+                let (from, to) = (
+                    self.start_angle + step * i as f64,
+                    self.start_angle + step * (i + 1) as f64,
+                );
+                // arms tangent to the arc, whose length makes the middle of the cubic on the arc
+                let arm = 4.0 / 3.0 * (step.to_radians() / 4.0).tan() * self.r;
+                let tangent_at =
+                    |angle: f64| Point::new(-angle.to_radians().sin(), angle.to_radians().cos());
+                let (origin, end) = (self.point_at(from), self.point_at(to));
+                Bezier::new_with_ctrl_points(vec![
+                    origin,
+                    origin + arm * tangent_at(from),
+                    end - arm * tangent_at(to),
+                    end,
+                ])
+            })
+            .collect()
+    }
+
+    /// Elliptical arc of the same points, whose angles are the same as the arc's
+    pub fn to_elliptical_arc(&self) -> EllipticalArc {
+        EllipticalArc::new(
+            self.origin,
+            self.r,
+            self.r,
+            0.0,
+            self.start_angle,
+            self.sweep_angle,
+        )
+    }
+
+    /// Transform by a transformation which keeps the circle round, such as rotation, mirror, and uniform scaling
+    ///
+    /// the arc turns the other way if the transformation mirrors it.
+    /// panics if the transformation stretches or shears the arc, like `Circle::transform`
+    pub fn transform(&mut self, m: &Transform2) {
+        assert!(
+            m.is_similarity(),
+            "An arc cannot be stretched or sheared, transform it as an elliptical arc"
+        );
+        let start = self.start().transform(m);
+        self.origin = self.origin.transform(m);
+        self.r *= m.determinant().abs().sqrt();
+        let v = start - self.origin;
+        self.start_angle = v.y.atan2(v.x).to_degrees();
+        if m.determinant() < 0.0 {
+            self.sweep_angle = -self.sweep_angle;
+        }
+    }
+
+    /// 1 if the arc goes counterclockwise, or -1 if clockwise
+    fn direction(&self) -> f64 {
+        if self.sweep_angle < 0.0 {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+}
+
+pub struct Split {
+    pub fst: Arc,
+    pub snd: Arc,
+}
+
+/// Angle equivalent to the given angle between start and start + sweep, or none if it is out of the sweep
+///
+/// angles slightly out of the sweep within the margin are rounded to the nearest edge
+/// - margin - in degrees
+pub(crate) fn angle_in_sweep(angle: f64, start: f64, sweep: f64, margin: f64) -> Option<f64> {
+    let direction = if sweep < 0.0 { -1.0 } else { 1.0 };
+    // how far the angle goes from the start in the direction of the sweep
+    let advance = ((angle - start) * direction).rem_euclid(360.0);
+    if advance <= sweep.abs() {
+        Some(start + direction * advance)
+    } else if advance <= sweep.abs() + margin {
+        Some(start + sweep)
+    } else if advance >= 360.0 - margin {
+        Some(start)
+    } else {
+        None
+    }
+}
+
+/// Number of cubic curves to approximate an arc of the radius and the sweep within the tolerance
+///
+/// the radial error of a cubic spanning the angle theta is r 2/27 sin^6(theta/4) / cos^2(theta/4)
+pub(crate) fn count_cubic_pieces(r: f64, sweep_angle: f64, tolerance: f64) -> usize {
+    let error = |count: usize| {
+        let quarter = sweep_angle.abs().to_radians() / count as f64 / 4.0;
+        r * 2.0 / 27.0 * quarter.sin().powi(6) / quarter.cos().powi(2)
+    };
+    let mut count = (sweep_angle.abs() / 90.0).ceil().max(1.0) as usize;
+    while error(count) > tolerance && count < 2 * 360 {
+        count += 1;
+    }
+    count
+}
+
+#[cfg(test)]
+mod test_arc {
+    use std::f64::consts::PI;
+
+    use crate::shapes::{arc::Arc, bezier::TOLERANCE, point::Point};
+
+    #[test]
+    fn test_len() {
+        let a = Arc::new(Point::new(1.0, 1.0), 2.0, 30.0, 90.0);
+        assert!((a.len() - PI).abs() < 1e-12);
+        let mut clockwise = a;
+        clockwise.reverse();
+        assert!((clockwise.len() - PI).abs() < 1e-12);
+        assert!(clockwise.start().distance(a.end()) < 1e-12);
+        assert!(a.point_at_length(PI / 2.0).distance(a.point_at(75.0)) < 1e-12);
+        assert!(
+            clockwise
+                .point_at_length(PI / 2.0)
+                .distance(a.point_at(75.0))
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_t_at_length_out_of_range() {
+        let a = Arc::new(Point::new(0.0, 0.0), 2.0, 30.0, -90.0);
+        assert_eq!(a.t_at_length(-1.0), 30.0);
+        assert!((a.t_at_length(a.len() + TOLERANCE / 2.0) + 60.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "Length is out of the arc")]
+    fn test_t_at_length_beyond_end() {
+        let a = Arc::new(Point::new(0.0, 0.0), 2.0, 30.0, 90.0);
+        a.t_at_length(a.len() + 1.0);
+    }
+
+    #[test]
+    fn test_to_beziers() {
+        let a = Arc::new(Point::new(1.0, -1.0), 10.0, -45.0, 270.0);
+        for tolerance in [0.1, 0.001, 0.00001].iter() {
+            let beziers = a.to_beziers(*tolerance);
+            assert!(beziers[0].origin().distance(a.start()) < 1e-12);
+            assert!(beziers[beziers.len() - 1].end().distance(a.end()) < 1e-12);
+            for b in beziers.iter() {
+                for k in 0..=50 {
+                    let p = b.point_at(k as f64 / 50.0);
+                    assert!((p.distance(a.origin) - a.r).abs() <= *tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_project() {
+        let a = Arc::new(Point::new(0.0, 0.0), 1.0, 0.0, 90.0);
+        let at = |angle: f64, r: f64| {
+            let theta = angle.to_radians();
+            Point::new(r * theta.cos(), r * theta.sin())
+        };
+        // inside the sweep
+        let projection = a.project(at(30.0, 2.0));
+        assert!((projection.t - 30.0).abs() < 1e-9 && (projection.distance - 1.0).abs() < 1e-12);
+        // just beyond the edges, the edges are the nearest
+        assert_eq!(a.project(at(-10.0, 2.0)).t, 0.0);
+        assert_eq!(a.project(at(100.0, 0.5)).t, 90.0);
+        // opposite to the arc, the nearer edge
+        assert_eq!(a.project(at(200.0, 1.0)).t, 90.0);
+        assert_eq!(a.project(at(-110.0, 1.0)).t, 0.0);
+        // clockwise arc, whose angles go below the start
+        let clockwise = Arc::new(Point::new(0.0, 0.0), 1.0, 0.0, -90.0);
+        assert!((clockwise.project(at(-30.0, 2.0)).t + 30.0).abs() < 1e-9);
+        assert_eq!(clockwise.project(at(10.0, 2.0)).t, 0.0);
+        assert_eq!(clockwise.project(at(-100.0, 2.0)).t, -90.0);
+    }
+}
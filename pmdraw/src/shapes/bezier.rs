@@ -1,144 +1,236 @@
-use pmmath::{binomial::binomial, matrix::Mat};
-
-use super::{
-    line::Line,
-    point::{sigma, Point},
+use pmmath::{
+    binomial::binomial_row,
+    matrix::{Mat, NotInvertible},
+    polynomial::Polynomial,
+    quadrature::{integrate, inverse_arc_length},
+    roots::{brent, RootError},
+    transform::Transform2,
 };
 
+use super::{lengths_every, line::Line, offset, point::Point, projection::Projection};
+
 /// A general bezier of n points
 pub struct Bezier {
     /// fit points - used to culculate parallel lines
     pub fit_points: Vec<Point>,
     /// origin, end, and control points
     points: Vec<Point>,
-    /// [Optional] - set range (from, to) to represent patial bezier curve
-    pub range: RangePoint,
+    /// [Optional] - set range of t (from, to) to represent patial bezier curve
+    range: RangeF64,
     t: Vec<f64>,
+    /// n-1C0, n-1C1, ..., n-1Cn-1 for n points, which are coefficients of bernstein polynomials
+    binomials: Vec<f64>,
+    /// dB/dt as a bezier curve
+    hodograph: Hodograph,
+    /// d^2B/dt^2 as a bezier curve
+    second_hodograph: Hodograph,
 }
 
 impl Bezier {
-    pub fn at_y(&self, y: f64) -> Point {
-        let tolerance = 0.1;
-        // initial guess
-        let mut t = 0.5;
-        loop {
-            // derivative at guess t
-            let dy_dt = self.derivative(t).y;
-            // update t by Newton's method
-            let y0 = self.point_at(t).y;
-            let t1 = match t + (y - y0) / dy_dt {
-                // set uppder bound and lowerbound to prevent diverge
-                t @ _ => {
-                    if t > 1.0 {
-                        1.0
-                    } else if t < 0.0 {
-                        0.0
-                    } else {
-                        t
-                    }
-                }
-            };
-            // validate new t
-            let p1 = self.point_at(t1);
-            let dif = y0 - p1.y;
-            if dif.abs() < tolerance && dif.abs() < tolerance {
-                return p1;
+    /// Approximate many points by a curve of fewer points in the least squares sense
+    ///
+    /// - points - points the curve goes near, such as measured or digitized ones
+    /// - count_points - number of origin, end, and control points of the curve
+    ///
+    /// the curve starts at the first point and ends at the last one,
+    /// and each point is associated with t in proportion to the distance along the points
+    pub fn approximate(points: &[Point], count_points: usize) -> Result<Bezier, FitError> {
+        let m = points.len();
+        assert!(count_points >= 2, "A curve needs at least origin and end");
+        assert!(m >= count_points, "Points must be more than the curve's");
+        let origin = points[0];
+        let end = points[m - 1];
+        if count_points == 2 {
+            return Ok(Bezier::new_with_ctrl_points(vec![origin, end]));
+        }
+
+        let t = Parameterization::ChordLength.t(points);
+
+        // A c = b where c are control points, solved for x and y at once
+        let n = count_points;
+        let mut a = Mat::zeros(m - 2, n - 2);
+        let mut b = Mat::zeros(m - 2, 2);
+        let binomials = binomial_row(n - 1);
+        for i in 1..m - 1 {
+            let ti = t[i];
+            for k in 1..n - 1 {
+                a[i - 1][k - 1] =
+                    (1.0 - ti).powf(n as f64 - 1.0 - k as f64) * ti.powf(k as f64) * binomials[k];
             }
-            // if t is not precise enough, repeat the process against updated t
-            t = t1;
+            let right_hand_side = points[i]
+                - ((1.0 - ti).powf(n as f64 - 1.0) * origin)
+                - (ti.powf(n as f64 - 1.0) * end);
+            b[i - 1][0] = right_hand_side.x;
+            b[i - 1][1] = right_hand_side.y;
+        }
+        let least_squares = a.least_squares(&b);
+        if least_squares.rank < n - 2 {
+            return Err(FitError::NotInvertible);
         }
+        let c = least_squares.solution;
+        let mut ctrl_points = vec![origin];
+        for k in 0..n - 2 {
+            ctrl_points.push(Point::new(c[k][0], c[k][1]));
+        }
+        ctrl_points.push(end);
+        Ok(Bezier::new_with_ctrl_points(ctrl_points))
     }
 
-    /// derivative dB/dt = (dx/dt, dy/dt)
-    pub fn derivative(&self, t: f64) -> Point {
-        if t == 1.0 {
-            (self.point_at(t) - self.point_at(t - 0.001)) / 0.001
-        } else {
-            (self.point_at(t + 0.001) - self.point_at(t)) / 0.001
+    /// Get the point on the curve by y
+    ///
+    /// panics if the curve does not reach y. use `try_at_y` to handle the error
+    pub fn at_y(&self, y: f64) -> Point {
+        self.try_at_y(y, TOLERANCE)
+            .expect("Failed to find the point at y")
+    }
+
+    /// origin, control points, and end
+    pub fn ctrl_points(&self) -> &Vec<Point> {
+        &self.points
+    }
+
+    /// Signed curvature at t, which is positive where the curve turns left (counterclockwise)
+    ///
+    ///  k = (B' x B'') / |B'|^3
+    pub fn curvature(&self, t: f64) -> f64 {
+        let (d1, d2) = (self.derivative(t), self.second_derivative(t));
+        let speed = d1.norm();
+        if speed == 0.0 {
+            return 0.0;
         }
+        (d1.x * d2.y - d1.y * d2.x) / speed.powi(3)
+    }
+
+    /// derivative dB/dt = (dx/dt, dy/dt), which is exact as a bezier curve of differences of control points
+    pub fn derivative(&self, t: f64) -> Point {
+        self.hodograph.point_at(t)
     }
 
     pub fn end(&self) -> Point {
         self.points[self.points.len() - 1]
     }
 
+    /// Parameters t in the range where x or y is locally minimum or maximum, in ascending order
+    ///
+    /// the curve is inside the bounding box of these points and the edges of the range
+    pub fn extrema(&self) -> Vec<f64> {
+        let mut extrema = self.horizontal_tangents();
+        extrema.extend(self.vertical_tangents());
+        extrema.sort_by(|t1, t2| t1.partial_cmp(t2).unwrap());
+        extrema.dedup();
+        extrema
+    }
+
+    /// Parameters t in the range where the tangent is horizontal (dy/dt = 0), in ascending order
+    pub fn horizontal_tangents(&self) -> Vec<f64> {
+        let (_, y_t) = self.polynomials();
+        self.roots_in_range(&y_t.derivative())
+    }
+
+    /// Parameters t in the range where the curve changes the direction of turning, in ascending order
+    ///
+    /// they are roots of B' x B'', where the signed curvature changes its sign
+    pub fn inflections(&self) -> Vec<f64> {
+        let (x_t, y_t) = self.polynomials();
+        let (dx, dy) = (x_t.derivative(), y_t.derivative());
+        let cross = &dx * dy.derivative() - &dy * dx.derivative();
+        self.roots_in_range(&cross)
+            .into_iter()
+            .filter(|t| {
+                // double roots only touch 0 without changing the direction
+                let h = TOLERANCE / self.max_speed();
+                cross.eval(t - h) * cross.eval(t + h) < 0.0
+            })
+            .collect()
+    }
+
     /// just get line from this bezier's end to the origin of another
     pub fn join(&self, b: &Bezier) -> Line {
-        self.range.to.line_to(b.range.from)
+        self.range().to.line_to(b.range().from)
     }
 
-    /// concatenate two bezier curves by extending edges as lines
+    /// concatenate two bezier curves by extending edges as lines along their tangents
+    ///
+    /// the lines meet halfway between the edges if the tangents are parallel
     pub fn join_by_extending(&self, b: &Bezier) -> (Line, Line) {
-        let mut bezier_edge1 = self
-            .range
-            .to
-            .line_to(self.point_at(self.t_range().to + 0.01));
-        let mut bezier_edge2 = b.point_at(b.t_range().from - 0.01).line_to(b.range.from);
-        bezier_edge1.join(&mut bezier_edge2);
-        (bezier_edge1, bezier_edge2)
+        let (end, origin) = (self.range().to, b.range().from);
+        let bezier_edge1 = end.line_to(end + self.tangent(self.t_range().to));
+        let bezier_edge2 = (origin - b.tangent(b.t_range().from)).line_to(origin);
+        let intersection = bezier_edge1
+            .intersection(&bezier_edge2)
+            .unwrap_or_else(|| end.middle(origin));
+        (end.line_to(intersection), intersection.line_to(origin))
     }
 
-    /// concatenate with a line by extending both of them
+    /// concatenate with a line by extending the line and the end of the curve along its tangent
+    ///
+    /// the line is kept and bridged from the end if they are parallel
     pub fn join_line(&mut self, l: &mut Line) -> Line {
-        let mut bezier_edge = self
-            .range
-            .to
-            .line_to(self.point_at(self.t_range().to + 0.01));
-        l.join(&mut bezier_edge);
-        bezier_edge
+        let end = self.range().to;
+        let bezier_edge = end.line_to(end + self.tangent(self.t_range().to));
+        match l.intersection(&bezier_edge) {
+            Some(intersection) => {
+                l.extend_to(intersection);
+                end.line_to(intersection)
+            }
+            None => end.line_to(l.origin),
+        }
     }
 
+    /// Length of the curve in its range
     pub fn len(&self) -> f64 {
-        let mut length = 0.0;
         let t_range = self.t_range();
-        let mut t = t_range.from;
-        let dt = 0.01;
-        loop {
-            let p0 = self.point_at(t);
-            let mut t1 = t + dt;
-            if t1 >= t_range.to {
-                t1 = t_range.to
-            }
-            let p1 = self.point_at(t1);
-            length += p0.distance(p1);
-            if t1 == t_range.to {
-                return length;
-            }
-            t = t1
-        }
+        integrate(
+            &|t| self.derivative(t).norm(),
+            t_range.from,
+            t_range.to,
+            TOLERANCE,
+        )
     }
 
+    /// Mirror the curve in its range and reverse it, like `Line::mirror`
     pub fn mirror(&self, mirror_line: Line) -> Bezier {
-        let mut mirrored_fit_points = Vec::new();
-        for p in &self.fit_points {
-            mirrored_fit_points.push(p.mirror(mirror_line))
-        }
-        let mut mirrored_b = Bezier::new_with_t(&mirrored_fit_points, &self.t);
-        let (from, to) = self.get_range_index();
-        mirrored_b.range.from = mirrored_fit_points[from];
-        mirrored_b.range.to = mirrored_fit_points[to];
+        let range = self.t_range();
+        let mirrored_points = self
+            .sub_curve(range.from, range.to)
+            .points
+            .iter()
+            .map(|p| p.mirror(mirror_line))
+            .collect();
+        let mut mirrored_b = Bezier::new_with_ctrl_points(mirrored_points);
         mirrored_b.reverse();
         mirrored_b
     }
 
-    /// fit points
+    /// fit points, parameterized centripetally
+    ///
+    /// unevenly spaced fit points make neither loops nor overshoots, unlike uniform t
     pub fn new(fit_points: Vec<Point>) -> Bezier {
-        let dt = 1.0 / (fit_points.len() as f64 - 1.0);
-        let t_parameters = (0..fit_points.len())
-            .map(|n| {
-                if (n as f64) * dt > 1.0 {
-                    1.0
-                } else {
-                    (n as f64) * dt
-                }
-            })
-            .collect::<Vec<f64>>();
-        Bezier::new_with_t(&fit_points, &t_parameters)
+        Bezier::new_with_parameterization(fit_points, Parameterization::Centripetal)
+    }
+
+    /// fit points and the strategy to decide t of each point
+    ///
+    /// panics if the fit points cannot define a curve
+    pub fn new_with_parameterization(
+        fit_points: Vec<Point>,
+        parameterization: Parameterization,
+    ) -> Bezier {
+        let t = parameterization.t(&fit_points);
+        Bezier::new_with_t(&fit_points, &t)
     }
 
     /// fit points and parameter values of each point
+    ///
+    /// panics if the fit points cannot define a curve. use `try_new_with_t` to handle the error
+    pub fn new_with_t(fit_points: &[Point], t: &[f64]) -> Bezier {
+        Bezier::try_new_with_t(fit_points, t).expect("Failed to fit bezier curve")
+    }
 
-    pub fn new_with_t(fit_points: &Vec<Point>, t: &Vec<f64>) -> Bezier {
+    /// fit points and parameter values of each point
+    ///
+    /// fails if control points are not solved reliably, e.g. some fit points share the same t
+    pub fn try_new_with_t(fit_points: &[Point], t: &[f64]) -> Result<Bezier, FitError> {
         let count_points = fit_points.len();
 
         // set origin and end
@@ -149,19 +241,57 @@ impl Bezier {
         points[count_points - 1] = end;
 
         // set ctrl points
-        let ctrl_points = solve_ctrl_points(fit_points, t);
+        let ctrl_points = solve_ctrl_points(fit_points, t)?;
         for i in 0..count_points - 2 {
             points[i + 1] = ctrl_points[i];
         }
-        Bezier {
-            fit_points: fit_points.clone(),
+        let (hodograph, second_hodograph) = hodographs(&points);
+        Ok(Bezier {
+            fit_points: fit_points.to_vec(),
             points,
-            range: RangePoint {
-                from: origin,
-                to: end,
-            },
-            t: t.clone(),
-        }
+            range: RangeF64 { from: 0.0, to: 1.0 },
+            t: t.to_vec(),
+            binomials: binomial_row(count_points - 1),
+            hodograph,
+            second_hodograph,
+        })
+    }
+
+    /// origin, control points, and end
+    ///
+    /// fit points are the points on the curve at even intervals of t
+    pub fn new_with_ctrl_points(points: Vec<Point>) -> Bezier {
+        let count_points = points.len();
+        let t = (0..count_points)
+            .map(|i| i as f64 / (count_points as f64 - 1.0))
+            .collect::<Vec<f64>>();
+        let (hodograph, second_hodograph) = hodographs(&points);
+        let mut b = Bezier {
+            fit_points: Vec::new(),
+            points,
+            range: RangeF64 { from: 0.0, to: 1.0 },
+            t,
+            binomials: binomial_row(count_points - 1),
+            hodograph,
+            second_hodograph,
+        };
+        b.fit_points = b.t.iter().map(|t| b.point_at(*t)).collect();
+        b
+    }
+
+    /// Unit normal at t, which is the unit tangent rotated 90 degrees counterclockwise
+    pub fn normal(&self, t: f64) -> Point {
+        let tangent = self.tangent(t);
+        Point::new(-tangent.y, tangent.x)
+    }
+
+    /// Curves at the distance from the curve in its range, within the tolerance
+    ///
+    /// positive distance is on the right side of the direction of the curve like `parallel(distance).right`,
+    /// and negative is on the left. the offset is approximated by as many cubic curves as needed,
+    /// and loops made where the curve turns tighter than the distance are trimmed
+    pub fn offset(&self, distance: f64, tolerance: f64) -> Vec<Bezier> {
+        offset::offset(std::slice::from_ref(self), distance, tolerance)
     }
 
     pub fn origin(&self) -> Point {
@@ -171,136 +301,168 @@ impl Bezier {
     /// A general bezier of n points (P0, P1, ..., Pk) is definde as
     ///  B(t) = Sigma[ n-1Ck * (1-t)^(n-1-k) * t^k * Pk ]
     ///   where 0 <= t <= 1
+    ///
+    /// evaluated like horner's method, which takes O(n) operations
     pub fn point_at(&self, t: f64) -> Point {
-        // number of points
-        let n = self.points.len();
-        sigma(
-            |k| {
-                (1.0 - t).powf(n as f64 - 1.0 - k as f64)
-                    * t.powf(k as f64)
-                    * binomial(n - 1, k) as f64
-                    * self.points[k]
-            },
-            0,
-            n,
+        bernstein(&self.points, &self.binomials, t)
+    }
+
+    /// Point at the length along the curve from the start of its range
+    pub fn point_at_length(&self, length: f64) -> Point {
+        self.point_at(self.t_at_length(length))
+    }
+
+    /// Points at even intervals of length along the curve from the start of its range
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(self.len(), interval)
+            .into_iter()
+            .map(|length| self.point_at(self.t_at_length(length)))
+            .collect()
+    }
+
+    /// x(t) and y(t) in the power basis, where 0 <= t <= 1 regardless of the range
+    pub fn polynomials(&self) -> (Polynomial, Polynomial) {
+        let xs = self.points.iter().map(|p| p.x).collect::<Vec<f64>>();
+        let ys = self.points.iter().map(|p| p.y).collect::<Vec<f64>>();
+        (
+            Polynomial::from_bernstein(&xs),
+            Polynomial::from_bernstein(&ys),
         )
     }
 
+    /// Nearest point on the curve in its range to p
+    ///
+    /// the distance is the smallest at an edge of the range or where (B(t) - p) . dB/dt = 0,
+    /// whose roots are all found as roots of a polynomial, hence the nearest point is global
+    pub fn project(&self, p: Point) -> Projection {
+        let range = self.t_range();
+        let (from, to) = if range.from <= range.to {
+            (range.from, range.to)
+        } else {
+            (range.to, range.from)
+        };
+        self.project_between(p, from, to, TOLERANCE)
+    }
+
     pub fn reverse(&mut self) -> () {
         self.fit_points.reverse();
         self.points.reverse();
+        self.update_hodographs();
         self.t = self.t.iter().map(|each_t| 1.0 - each_t).collect();
         self.t.reverse();
-        let from = self.range.from.clone();
-        self.range.from = self.range.to;
-        self.range.to = from;
+        self.range = RangeF64 {
+            from: 1.0 - self.range.to,
+            to: 1.0 - self.range.from,
+        };
+    }
+
+    /// second derivative d^2B/dt^2, which is exact as the derivative of the derivative
+    pub fn second_derivative(&self, t: f64) -> Point {
+        self.second_hodograph.point_at(t)
     }
 
+    /// Edges of the range on the curve
+    pub fn range(&self) -> RangePoint {
+        RangePoint {
+            from: self.point_at(self.range.from),
+            to: self.point_at(self.range.to),
+        }
+    }
+
+    /// Set the range by its edges on the curve, whose t is solved once here
     pub fn set_range(&mut self, from: Point, to: Point) {
-        self.range = RangePoint { from, to };
+        self.range = RangeF64 {
+            from: self.solve_t_at(from),
+            to: self.solve_t_at(to),
+        };
     }
 
-    /// Solve parameter t of point p when p is somewhere on the curve by Newton's method
+    /// Solve parameter t of point p when p is somewhere on the curve
+    ///
+    /// panics if the solver does not converge. use `try_solve_t_at` to handle the error
     pub fn solve_t_at(&self, p: Point) -> f64 {
-        // if p is one of fit points, t is known
-        for i in 0..self.fit_points.len() {
-            if p == self.fit_points[i] {
-                return self.t[i];
-            }
-        }
-        // otherwise, calculate t
-        let tolerance = 0.1;
-        let (mut learning_rate_x, mut learning_rate_y) = (1.0, 1.0);
-        // initial guess
-        let mut t = 0.5;
-        loop {
-            // derivative at guess t
-            let db_dt = self.derivative(t);
-            // update t by Newton's method
-            let p0 = self.point_at(t);
-            let t1 = match t
-                + learning_rate_y * (p.y - p0.y) / db_dt.y
-                + learning_rate_x * (p.x - p0.x) / db_dt.x
-            {
-                // set uppder bound and lowerbound to prevent diverge
-                t @ _ => {
-                    if t > 1.0 {
-                        1.0
-                    } else if t < 0.0 {
-                        0.0
-                    } else {
-                        t
-                    }
-                }
-            };
-            // validate new t
-            let p1 = self.point_at(t1);
-            let dif = p - p1;
-            if dif.x.abs() < tolerance && dif.y.abs() < tolerance {
-                return t1;
-            }
-            // if t is not precise enough, repeat the process against updated t
-            t = t1;
-            // modify learning rate to prevent surturation and divergence
-            // learn more if difference is large
-            learning_rate_x = dif.x.abs() / (dif.x.abs() + dif.y.abs());
-            learning_rate_y = dif.y.abs() / (dif.x.abs() + dif.y.abs());
-        }
+        self.try_solve_t_at(p, TOLERANCE)
+            .expect("Failed to solve t")
     }
 
-    /// Split the curve and get two curves
+    /// Split the curve at the point on it and get two curves
     pub fn split(&self, p: Point) -> Split {
-        let mut b1 = self.clone();
-        b1.set_range(self.origin(), p);
-        let mut b2 = self.clone();
-        b2.set_range(p, self.end());
-        Split { fst: b1, snd: b2 }
+        self.split_at_t(self.solve_t_at(p))
     }
 
-    /// Get range of t (0 to 1 by default)
-    pub fn t_range(&self) -> RangeF64 {
-        let range = self.range;
-        let from = if range.from == self.origin() {
-            0.0
+    /// Split the curve at the length along the curve from the start of its range
+    pub fn split_at_length(&self, length: f64) -> Split {
+        self.split_at_t(self.t_at_length(length))
+    }
+
+    /// Split the curve at t and get two independent curves with their own control points
+    ///
+    /// if the curve has a range, parts out of the range are dropped
+    pub fn split_at_t(&self, t: f64) -> Split {
+        let t_range = self.t_range();
+        Split {
+            fst: self.sub_curve(t_range.from, t),
+            snd: self.sub_curve(t, t_range.to),
+        }
+    }
+
+    /// Part of the curve from t0 to t1 as an independent curve, computed by de casteljau's algorithm
+    pub fn sub_curve(&self, t0: f64, t1: f64) -> Bezier {
+        let (_, right) = de_casteljau(&self.points, t0);
+        // t1 in the right part, where t0 is 0 and 1 is 1
+        let t1 = if t0 < 1.0 {
+            (t1 - t0) / (1.0 - t0)
         } else {
-            self.solve_t_at(range.from)
-        };
-        let to = if range.to == self.end() {
             1.0
-        } else {
-            self.solve_t_at(range.to)
         };
-        RangeF64 { from, to }
+        let (points, _) = de_casteljau(&right, t1);
+        Bezier::new_with_ctrl_points(points)
     }
 
+    /// Get range of t (0 to 1 by default)
+    pub fn t_range(&self) -> RangeF64 {
+        self.range
+    }
+
+    /// Curves at the distance on the left and the right of the curve in its range
+    ///
+    /// each side is the offset within the tolerance. if the offset needs more than one piece,
+    /// they are approximated by a curve of as few points as possible, but no fewer than this one
     pub fn parallel(&self, distance: f64) -> Parallel {
-        let make_parallel = |is_left: bool| {
-            let mut fit_poitns = Vec::new();
-            for i in 0..self.points.len() {
-                let mut p = self.fit_points[i];
-                let mut p_next = self.point_at(self.solve_t_at(self.fit_points[i]) + 0.01);
-                // t is defined in [0, 1], hence modify points to make sure they are inside the interval
-                if i == self.points.len() - 1 {
-                    p_next = p;
-                    p = self.point_at(0.99);
+        let make_parallel = |distance: f64| {
+            let mut pieces = self.offset(distance, TOLERANCE);
+            if pieces.len() == 1 {
+                return pieces.remove(0);
+            }
+            let mut samples = vec![pieces[0].origin()];
+            for b in &pieces {
+                samples.extend((1..=SAMPLES).map(|i| b.point_at(i as f64 / SAMPLES as f64)));
+            }
+            let mut count_points = self.points.len().max(4);
+            loop {
+                let b = Bezier::approximate(&samples, count_points)
+                    .expect("Failed to approximate the offset");
+                let error = samples
+                    .iter()
+                    .map(|p| b.project(*p).distance)
+                    .fold(0.0, f64::max);
+                if error <= TOLERANCE || count_points >= MAX_PARALLEL_POINTS {
+                    return b;
                 }
-                let mut parallel_point = p_next.clone();
-                parallel_point.rotate(if is_left { 90.0 } else { -90.0 }, p);
-                parallel_point = p.to_point(parallel_point, distance);
-                fit_poitns.push(parallel_point);
+                count_points += 1;
             }
-            let mut parallel_bezier = Bezier::new_with_t(&fit_poitns, &self.t);
-            let (from, to) = self.get_range_index();
-            parallel_bezier.range.from = fit_poitns[from];
-            parallel_bezier.range.to = fit_poitns[to];
-            parallel_bezier
         };
         Parallel {
-            left: make_parallel(true),
-            right: make_parallel(false),
+            left: make_parallel(-distance),
+            right: make_parallel(distance),
         }
     }
 
+    /// Radius of the circle which osculates the curve at t, which is infinite where the curve is straight
+    pub fn radius_of_curvature(&self, t: f64) -> f64 {
+        1.0 / self.curvature(t).abs()
+    }
+
     /// refit bezier curve with new fit_points
     pub fn refit<T>(
         &mut self,
@@ -311,26 +473,45 @@ impl Bezier {
     where
         T: FnMut(&mut Vec<Point>) -> Vec<Point>,
     {
+        let range = self.range();
         let new_b = Bezier::new(modify(&mut self.fit_points));
         self.fit_points = new_b.fit_points;
         self.points = new_b.points;
-        self.range.from = match range_from {
-            Some(p) => p,
-            None => self.range.from,
-        };
-        self.range.to = match range_end {
-            Some(p) => p,
-            None => self.range.to,
-        };
+        self.t = new_b.t;
+        self.binomials = new_b.binomials;
+        self.hodograph = new_b.hodograph;
+        self.second_hodograph = new_b.second_hodograph;
+        self.set_range(
+            range_from.unwrap_or(range.from),
+            range_end.unwrap_or(range.to),
+        );
     }
 
     /// Rotate around point
     pub fn rotate(&mut self, angle_degree: f64, around: Point) -> () {
-        for i in 0..self.points.len() {
-            self.points[i].rotate(angle_degree, around);
+        self.transform(&Transform2::rotate_around(angle_degree, around.x, around.y));
+    }
+
+    /// Unit tangent at the length along the curve from the start of its range
+    pub fn tangent_at_length(&self, length: f64) -> Point {
+        self.tangent(self.t_at_length(length))
+    }
+
+    /// Unit tangent at t in the direction of the curve
+    ///
+    /// where the curve stops, i.e. dB/dt = 0, the direction is taken from the next nonzero derivative
+    pub fn tangent(&self, t: f64) -> Point {
+        let d1 = self.derivative(t);
+        if d1.norm() > f64::EPSILON * self.max_speed() {
+            return d1.normalize();
+        }
+        // B'(t + h) ~ h B''(t), which points backward at the end
+        let d2 = self.second_derivative(t);
+        if t < 1.0 {
+            d2.normalize()
+        } else {
+            -1.0 * d2.normalize()
         }
-        self.range.from.rotate(angle_degree, around);
-        self.range.to.rotate(angle_degree, around);
     }
 
     pub fn to(&mut self, dx: f64, dy: f64) {
@@ -340,19 +521,158 @@ impl Bezier {
         for i in 0..self.points.len() {
             self.points[i] = self.points[i].to(dx, dy);
         }
-        self.range.from = self.range.from.to(dx, dy);
-        self.range.to = self.range.to.to(dx, dy);
+    }
+
+    /// Solve parameter t of the point at the length along the curve from the start of its range
+    ///
+    /// panics if the length exceeds the curve. use `try_t_at_length` to handle the error
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        self.try_t_at_length(length, TOLERANCE)
+            .expect("Length is out of the curve")
+    }
+
+    /// Solve parameter t of the point at the length along the curve from the start of its range
+    /// - tolerance - error of the length in centimeters
+    pub fn try_t_at_length(&self, length: f64, tolerance: f64) -> Result<f64, RootError> {
+        let t_range = self.t_range();
+        inverse_arc_length(
+            &|t| self.derivative(t).norm(),
+            t_range.from,
+            t_range.to,
+            length,
+            tolerance,
+        )
+    }
+
+    /// Get the point on the curve by y
+    ///
+    /// if the curve crosses y more than once, the point nearest to the middle of the curve is chosen
+    /// - tolerance - error of the point in centimeters
+    pub fn try_at_y(&self, y: f64, tolerance: f64) -> Result<Point, RootError> {
+        let (_, y_t) = self.polynomials();
+        let roots = (y_t - Polynomial::new(vec![y])).roots(0.0, 1.0, tolerance / self.max_speed());
+        let t = roots
+            .into_iter()
+            .fold(None, |nearest: Option<f64>, t| match nearest {
+                Some(nearest) if (nearest - 0.5).abs() <= (t - 0.5).abs() => Some(nearest),
+                _ => Some(t),
+            })
+            .ok_or(RootError::NotBracketed)?;
+        Ok(self.point_at(t))
+    }
+
+    /// Solve parameter t of point p when p is somewhere on the curve
+    ///
+    /// if p is slightly off the curve, t of the nearest point on the curve is solved
+    /// - tolerance - error of the point in centimeters
+    pub fn try_solve_t_at(&self, p: Point, tolerance: f64) -> Result<f64, RootError> {
+        // if p is one of fit points, t is known
+        for i in 0..self.fit_points.len() {
+            if p == self.fit_points[i] {
+                return Ok(self.t[i]);
+            }
+        }
+        // otherwise, find the nearest sample and refine it
+        let dt = 1.0 / SAMPLES as f64;
+        let nearest = (0..SAMPLES + 1)
+            .map(|i| i as f64 * dt)
+            .fold((0.0, f64::INFINITY), |(nearest_t, min), t| {
+                let d = self.point_at(t).distance(p);
+                if d < min {
+                    (t, d)
+                } else {
+                    (nearest_t, min)
+                }
+            })
+            .0;
+        // distance is minimized where (B(t) - p) . dB/dt = 0
+        let g = |t: f64| {
+            let v = self.point_at(t) - p;
+            let db_dt = self.derivative(t);
+            v.x * db_dt.x + v.y * db_dt.y
+        };
+        let (from, to) = ((nearest - dt).max(0.0), (nearest + dt).min(1.0));
+        match brent(g, from, to, tolerance / self.max_speed(), MAX_ITERATIONS) {
+            Ok(root) => Ok(root.x),
+            // the nearest point is the origin or the end, or out of the samples around it
+            Err(RootError::NotBracketed) => Ok(self.project_between(p, 0.0, 1.0, tolerance).t),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parameters t in the range where the tangent is vertical (dx/dt = 0), in ascending order
+    pub fn vertical_tangents(&self) -> Vec<f64> {
+        let (x_t, _) = self.polynomials();
+        self.roots_in_range(&x_t.derivative())
+    }
+
+    /// Roots of polynomial of t in the range
+    pub(crate) fn roots_in_range(&self, p: &Polynomial) -> Vec<f64> {
+        let range = self.t_range();
+        let (from, to) = if range.from <= range.to {
+            (range.from, range.to)
+        } else {
+            (range.to, range.from)
+        };
+        p.roots(from, to, TOLERANCE / self.max_speed())
+    }
+
+    /// Nearest point on the curve between t0 and t1 to p, where t0 <= t1
+    ///
+    /// unlike `project`, it does not need the range, thus solving t of the edges of the range can rely on it
+    fn project_between(&self, p: Point, t0: f64, t1: f64, tolerance: f64) -> Projection {
+        let (x_t, y_t) = self.polynomials();
+        let dx = &x_t - Polynomial::new(vec![p.x]);
+        let dy = &y_t - Polynomial::new(vec![p.y]);
+        let g = &dx * x_t.derivative() + &dy * y_t.derivative();
+        let mut candidates = vec![t0, t1];
+        candidates.extend(g.roots(t0, t1, tolerance / self.max_speed()));
+        Projection::nearest(
+            candidates
+                .into_iter()
+                .map(|t| Projection::new(t, self.point_at(t), p)),
+        )
+        .expect("Edges are always candidates")
+    }
+
+    /// Upper bound of |dB/dt|, which is (n-1) times the longest edge of control polygon
+    fn max_speed(&self) -> f64 {
+        let n = self.points.len();
+        let longest_edge = (1..n)
+            .map(|i| self.points[i - 1].distance(self.points[i]))
+            .fold(0.0, f64::max);
+        ((n - 1) as f64 * longest_edge).max(f64::EPSILON)
+    }
+
+    /// Derivatives follow control points after they change
+    fn update_hodographs(&mut self) {
+        let (hodograph, second_hodograph) = hodographs(&self.points);
+        self.hodograph = hodograph;
+        self.second_hodograph = second_hodograph;
+    }
+
+    /// Transform by an affine transformation
+    ///
+    /// the transformed control points define the transformed curve thanks to affine invariance of bezier curves.
+    /// unlike `mirror`, the direction of the curve is kept even if the transformation mirrors it
+    pub fn transform(&mut self, m: &Transform2) {
+        for p in &mut self.fit_points {
+            *p = p.transform(m);
+        }
+        for p in &mut self.points {
+            *p = p.transform(m);
+        }
+        self.update_hodographs();
     }
 
     pub fn get_range_index(&self) -> (usize, usize) {
         let mut from = 0;
         let mut to = 1;
         for i in 0..self.points.len() {
-            let p = self.fit_points[i];
-            if p == self.range.from {
+            if self.t[i] == self.range.from {
                 from = i
             }
-            if p == self.range.to {
+            if self.t[i] == self.range.to {
                 to = i
             }
         }
@@ -367,10 +687,36 @@ impl Clone for Bezier {
             points: self.points.clone(),
             range: self.range,
             t: self.t.clone(),
+            binomials: self.binomials.clone(),
+            hodograph: self.hodograph.clone(),
+            second_hodograph: self.second_hodograph.clone(),
         }
     }
 }
 
+/// Control points of a derivative of bezier curve and their binomial coefficients,
+/// which are kept since derivatives are evaluated many times, e.g. to integrate the length
+#[derive(Clone)]
+struct Hodograph {
+    points: Vec<Point>,
+    binomials: Vec<f64>,
+}
+
+impl Hodograph {
+    /// Derivative of bezier curve of points
+    fn new(points: &[Point]) -> Hodograph {
+        let points = differences(points);
+        Hodograph {
+            binomials: binomial_row(points.len().max(1) - 1),
+            points,
+        }
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        bernstein(&self.points, &self.binomials, t)
+    }
+}
+
 /// calculate control points from the points that the curve passes through  
 ///
 ///"algorithm to solve control points c1 to cn-2"
@@ -397,8 +743,8 @@ impl Clone for Bezier {
 ///this filles all the rows of constraint matrix A and
 ///all the elements of the vector b,
 ///and control points are solved by a linear equasion A c = b
-///thus,  c = A^-1 * b; */
-fn solve_ctrl_points(points: &Vec<Point>, t: &Vec<f64>) -> Vec<Point> {
+///by LU decomposition with partial pivoting
+fn solve_ctrl_points(points: &[Point], t: &[f64]) -> Result<Vec<Point>, FitError> {
     // number of points (p0, ..., pn-1)
     let n = points.len();
 
@@ -418,14 +764,14 @@ fn solve_ctrl_points(points: &Vec<Point>, t: &Vec<f64>) -> Vec<Point> {
     */
     let p0 = points[0];
     let pn_1 = points[n - 1];
+    let binomials = binomial_row(n - 1);
     for i in 1..n - 1 {
         let pi = points[i];
         let ti = t[i];
         for k in 1..n - 1 {
             // (1-ti)^(n-1-k) * ti^k n-1Ck pk
-            let coefficient_k = (1.0 - ti).powf(n as f64 - 1.0 - k as f64)
-                * ti.powf(k as f64)
-                * binomial(n - 1, k) as f64;
+            let coefficient_k =
+                (1.0 - ti).powf(n as f64 - 1.0 - k as f64) * ti.powf(k as f64) * binomials[k];
             // pi -(1-t)^(n-1) p0 - t^(n-1) pn-1
             let right_hand_side =
                 pi - ((1.0 - ti).powf(n as f64 - 1.0) * p0) - (ti.powf(n as f64 - 1.0) * pn_1);
@@ -437,14 +783,139 @@ fn solve_ctrl_points(points: &Vec<Point>, t: &Vec<f64>) -> Vec<Point> {
     }
 
     // solve
-    let c = a.inverse().expect("Not invertible") * b;
+    let lu = a.lu().map_err(|NotInvertible| FitError::NotInvertible)?;
+    let condition = lu.condition_estimate();
+    if condition > MAX_CONDITION {
+        return Err(FitError::IllConditioned { condition });
+    }
+    let c = lu.solve(&b);
 
     // convert to vector
     let mut ctrl_points = vec![Point::new(0.0, 0.0); n - 2];
     for i in 0..n - 2 {
         ctrl_points[i] = Point::new(c[2 * i][0], c[2 * i + 1][0]);
     }
-    ctrl_points
+    Ok(ctrl_points)
+}
+
+/// Evaluate bezier curve of points at t like horner's method, which takes O(n) operations
+///
+/// - binomials - n-1C0, n-1C1, ..., n-1Cn-1 for n points
+fn bernstein(points: &[Point], binomials: &[f64], t: f64) -> Point {
+    if points.is_empty() {
+        return Point::new(0.0, 0.0);
+    }
+    let n = points.len() - 1;
+    if n == 0 {
+        return points[0];
+    }
+    let s = 1.0 - t;
+    // t^k
+    let mut t_k = 1.0;
+    let mut sum = s * points[0];
+    for k in 1..n {
+        t_k *= t;
+        sum = s * (sum + t_k * binomials[k] * points[k]);
+    }
+    sum + t_k * t * points[n]
+}
+
+/// First and second derivatives of bezier curve of points
+fn hodographs(points: &[Point]) -> (Hodograph, Hodograph) {
+    let hodograph = Hodograph::new(points);
+    let second_hodograph = Hodograph::new(&hodograph.points);
+    (hodograph, second_hodograph)
+}
+
+/// Control points of the derivative of bezier curve of points, i.e. its hodograph
+///
+///  n (P1 - P0), n (P2 - P1), ..., n (Pn - Pn-1)
+fn differences(points: &[Point]) -> Vec<Point> {
+    let n = points.len() as f64 - 1.0;
+    points.windows(2).map(|p| n * (p[1] - p[0])).collect()
+}
+
+/// Split control points at t into control points of two curves by de casteljau's algorithm
+fn de_casteljau(points: &[Point], t: f64) -> (Vec<Point>, Vec<Point>) {
+    let n = points.len();
+    let mut left = Vec::with_capacity(n);
+    let mut right = Vec::with_capacity(n);
+    let mut points = points.to_vec();
+    for k in 0..n {
+        left.push(points[0]);
+        right.push(points[n - 1 - k]);
+        for i in 0..n - 1 - k {
+            points[i] = points[i] + t * (points[i + 1] - points[i]);
+        }
+    }
+    right.reverse();
+    (left, right)
+}
+
+/// default tolerance of solvers in centimeters
+pub const TOLERANCE: f64 = 0.001;
+/// solvers give up after this number of iterations
+pub const MAX_ITERATIONS: usize = 100;
+/// number of intervals to look for initial guesses of solvers
+const SAMPLES: usize = 32;
+/// most points of a curve which approximates pieces of the offset in `parallel`
+const MAX_PARALLEL_POINTS: usize = 16;
+
+/// condition number beyond which control points are regarded as garbage
+const MAX_CONDITION: f64 = 1e10;
+
+/// Reason why a curve cannot pass through fit points
+#[derive(Debug, Clone)]
+pub enum FitError {
+    /// fit points are degenerate, e.g. two of them share the same t or they are too few
+    NotInvertible,
+    /// control points are too sensitive to the fit points to be trusted
+    IllConditioned { condition: f64 },
+}
+
+/// Strategy to decide parameter t of each fit point
+#[derive(Debug, Clone)]
+pub enum Parameterization {
+    /// t at even intervals, which makes curves wiggle if fit points are unevenly spaced
+    Uniform,
+    /// t in proportion to the distance along fit points
+    ChordLength,
+    /// t in proportion to the square root of the distance along fit points,
+    /// which keeps curves tight around sharp turns
+    Centripetal,
+    /// t of each fit point from 0 to 1
+    Custom(Vec<f64>),
+}
+
+impl Parameterization {
+    /// Parameter t of each fit point, from 0 at the origin to 1 at the end
+    pub fn t(&self, fit_points: &[Point]) -> Vec<f64> {
+        let count_points = fit_points.len();
+        let alpha = match self {
+            Parameterization::Uniform => 0.0,
+            Parameterization::ChordLength => 1.0,
+            Parameterization::Centripetal => 0.5,
+            Parameterization::Custom(t) => {
+                assert_eq!(t.len(), count_points, "Each fit point needs its t");
+                return t.clone();
+            }
+        };
+        let mut t = vec![0.0; count_points];
+        for i in 1..count_points {
+            t[i] = t[i - 1] + fit_points[i - 1].distance(fit_points[i]).powf(alpha);
+        }
+        let total = t[count_points - 1];
+        t.iter()
+            .enumerate()
+            .map(|(i, t)| {
+                if total > 0.0 {
+                    t / total
+                } else {
+                    i as f64 / (count_points - 1) as f64
+                }
+            })
+            .collect()
+    }
 }
 
 pub struct Split {
@@ -457,6 +928,7 @@ pub struct RangePoint {
     pub from: Point,
     pub to: Point,
 }
+#[derive(Copy, Clone)]
 pub struct RangeF64 {
     pub from: f64,
     pub to: f64,
@@ -466,3 +938,287 @@ pub struct Parallel {
     pub left: Bezier,
     pub right: Bezier,
 }
+
+#[cfg(test)]
+mod test_bezier {
+    use crate::shapes::{
+        bezier::{Bezier, FitError, TOLERANCE},
+        line::Line,
+        point::Point,
+    };
+    use pmmath::roots::RootError;
+
+    #[test]
+    fn test_approximate() {
+        // points sampled from a cubic are approximated closely by a cubic, which is not exactly the same
+        // since t is in proportion to the distance along the points, and more closely by more control points
+        let cubic = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(5.0, 0.0),
+        ]);
+        let points = (0..=20)
+            .map(|i| cubic.point_at(i as f64 / 20.0))
+            .collect::<Vec<Point>>();
+        let error = |count_points: usize| {
+            let approximation = Bezier::approximate(&points, count_points).unwrap();
+            assert!(approximation.origin() == points[0] && approximation.end() == points[20]);
+            points
+                .iter()
+                .map(|p| approximation.project(*p).distance)
+                .fold(0.0, f64::max)
+        };
+        assert!(error(4) < 0.15);
+        assert!(error(6) < error(4) && error(6) < 0.02);
+    }
+
+    #[test]
+    fn test_approximate_noisy() {
+        // points scattered around a line are approximated by a curve between them
+        let points = (0..=10)
+            .map(|i| Point::new(i as f64, if i % 2 == 0 { 0.1 } else { -0.1 }))
+            .collect::<Vec<Point>>();
+        let approximation = Bezier::approximate(&points, 3).unwrap();
+        for i in 0..=10 {
+            assert!(approximation.point_at(i as f64 / 10.0).y.abs() <= 0.1);
+        }
+    }
+
+    #[test]
+    fn test_approximate_not_invertible() {
+        // all the inner points are at the origin, hence t of them is 0 where no control point is solved
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+        ];
+        assert!(matches!(
+            Bezier::approximate(&points, 3),
+            Err(FitError::NotInvertible)
+        ));
+    }
+
+    #[test]
+    fn test_try_at_y_horizontal_tangent() {
+        // the top of the arch, where the tangent is horizontal and y is a double root
+        let arch = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, 0.0),
+        ]);
+        let p = arch.try_at_y(1.0, 1e-6).unwrap();
+        assert!((p.x - 1.0).abs() < 1e-3 && (p.y - 1.0).abs() < 1e-6);
+        // above the top, y is never reached
+        assert!(matches!(
+            arch.try_at_y(1.5, 1e-6),
+            Err(RootError::NotBracketed)
+        ));
+    }
+
+    #[test]
+    fn test_try_solve_t_at() {
+        let b = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(5.0, 0.0),
+        ]);
+        let t = b.try_solve_t_at(b.point_at(0.3), 1e-6).unwrap();
+        assert!((t - 0.3).abs() < 1e-5);
+        // a point slightly off the curve gives t of the nearest point on the curve
+        let on_curve = b.point_at(0.6);
+        let off_curve = on_curve + 0.01 * b.normal(0.6);
+        let t = b.try_solve_t_at(off_curve, 1e-6).unwrap();
+        assert!((t - 0.6).abs() < 1e-5);
+        // a point beyond the end gives the end
+        let t = b.try_solve_t_at(Point::new(6.0, -1.0), 1e-6).unwrap();
+        assert!((t - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_point_at_high_degree() {
+        // binomial coefficients of degree above 33 overflow u32, which point_at must not depend on
+        let points = (0..=40)
+            .map(|k| Point::new(k as f64, ((k * 7) % 11) as f64))
+            .collect::<Vec<Point>>();
+        let b = Bezier::new_with_ctrl_points(points.clone());
+        let de_casteljau = |t: f64| {
+            let mut points = points.clone();
+            while points.len() > 1 {
+                points = points.windows(2).map(|p| p[0].between(p[1], t)).collect();
+            }
+            points[0]
+        };
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            assert!(b.point_at(t).distance(de_casteljau(t)) < 1e-9);
+        }
+    }
+
+    fn arch() -> Bezier {
+        Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(5.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn test_range() {
+        let mut b = arch();
+        b.set_range(b.point_at(0.2), b.point_at(0.7));
+        let range = b.t_range();
+        // t is solved within the tolerance of points
+        assert!(b.range().from.distance(arch().point_at(0.2)) < TOLERANCE);
+        assert!(b.range().to.distance(arch().point_at(0.7)) < TOLERANCE);
+        let len = b.len();
+        assert!((len - b.sub_curve(range.from, range.to).len()).abs() < 1e-9);
+        // the range follows the curve when it is reversed or moved
+        b.reverse();
+        let reversed_range = b.t_range();
+        assert!(reversed_range.from == 1.0 - range.to && reversed_range.to == 1.0 - range.from);
+        assert!(b.range().from.distance(arch().point_at(0.7)) < TOLERANCE);
+        b.to(1.0, 1.0);
+        assert!((b.len() - len).abs() < 1e-9);
+        assert!(b.range().to.distance(arch().point_at(0.2).to(1.0, 1.0)) < TOLERANCE);
+    }
+
+    #[test]
+    fn test_mirror() {
+        let mut b = arch();
+        b.set_range(b.point_at(0.2), b.point_at(0.7));
+        let mirror_line = Line::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0));
+        let mirrored = b.mirror(mirror_line);
+        // the part in the range is mirrored and reversed
+        let mirrored_range = mirrored.t_range();
+        assert!(mirrored_range.from == 0.0 && mirrored_range.to == 1.0);
+        let range = b.t_range();
+        for i in 0..=10 {
+            let t = i as f64 / 10.0;
+            let p = b.point_at(range.to - (range.to - range.from) * t);
+            assert!(mirrored.point_at(t).distance(Point::new(p.x, -p.y)) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_parallel() {
+        let b = arch();
+        let parallel = b.parallel(0.5);
+        for (side, normal) in [(&parallel.left, 0.5), (&parallel.right, -0.5)] {
+            assert!(side.origin().distance(b.origin() + normal * b.normal(0.0)) < 1e-9);
+            assert!(side.end().distance(b.end() + normal * b.normal(1.0)) < 1e-9);
+            for i in 0..=20 {
+                let p = side.point_at(i as f64 / 20.0);
+                assert!((b.project(p).distance - 0.5).abs() <= 2.0 * TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn test_join() {
+        // the end of the arch goes down, and the line goes left below it
+        let mut b = arch();
+        let mut l = Line::new(Point::new(4.0, -2.0), Point::new(0.0, -2.0));
+        let bridge = b.join_line(&mut l);
+        // the curve is extended along its tangent until the line, which is extended to meet it
+        assert!(bridge.origin == b.end());
+        assert!(bridge.vec().normalize().distance(b.tangent(1.0)) < 1e-9);
+        assert!(l.origin.distance(bridge.end) < 1e-9 && l.end == Point::new(0.0, -2.0));
+        // the line goes up into the origin of the arch
+        let mut l = Line::new(Point::new(-2.0, -4.0), Point::new(-1.0, -3.0));
+        let bridge = l.join_bezier(&mut b);
+        assert!(bridge.origin == b.origin());
+        assert!(bridge.vec().normalize().distance(-1.0 * b.tangent(0.0)) < 1e-9);
+        assert!(l.end.distance(bridge.end) < 1e-9);
+        // two arches side by side meet where their edges are extended
+        let mut next = arch();
+        next.to(6.0, 0.0);
+        let (fst, snd) = b.join_by_extending(&next);
+        assert!(fst.origin == b.end() && snd.end == next.origin());
+        assert!(fst.end.distance(snd.origin) < 1e-9);
+        assert!(fst.end.distance(Point::new(5.5, -1.5)) < 1e-9);
+    }
+
+    #[test]
+    fn test_derivative() {
+        let b = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(3.0, 3.0),
+            Point::new(4.0, 0.0),
+        ]);
+        // 3 ((1-t)^2 (P1-P0) + 2 (1-t) t (P2-P1) + t^2 (P3-P2))
+        let exact = |t: f64| {
+            3.0 * ((1.0 - t) * (1.0 - t) * Point::new(1.0, 2.0)
+                + 2.0 * (1.0 - t) * t * Point::new(2.0, 1.0)
+                + t * t * Point::new(1.0, -3.0))
+        };
+        for t in [0.0, 0.3, 0.5, 1.0].iter() {
+            assert!(b.derivative(*t).distance(exact(*t)) < 1e-12);
+        }
+        // 6 (P3 - 2 P2 + P1) at the end
+        assert!(b.second_derivative(1.0).distance(Point::new(-6.0, -24.0)) < 1e-12);
+        assert!(b.tangent(1.0).distance(Point::new(1.0, -3.0).normalize()) < 1e-12);
+        // the curve stops at the end, whose direction is the last arm
+        let stopping = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(4.0, 0.0),
+            Point::new(4.0, 0.0),
+        ]);
+        assert!(
+            stopping
+                .tangent(1.0)
+                .distance(Point::new(3.0, -2.0).normalize())
+                < 1e-12
+        );
+    }
+
+    #[test]
+    fn test_curvature() {
+        // y = x^2, whose curvature at the vertex is 2
+        let mut parabola = Bezier::new_with_ctrl_points(vec![
+            Point::new(-1.0, 1.0),
+            Point::new(0.0, -1.0),
+            Point::new(1.0, 1.0),
+        ]);
+        assert!((parabola.curvature(0.5) - 2.0).abs() < 1e-12);
+        assert!((parabola.radius_of_curvature(0.5) - 0.5).abs() < 1e-12);
+        assert!(parabola.normal(0.5).distance(Point::new(0.0, 1.0)) < 1e-12);
+        // 2 / (1 + 4 x^2)^(3/2) at x = 1
+        assert!((parabola.curvature(1.0) - 2.0 / 5.0_f64.powf(1.5)).abs() < 1e-12);
+        // turning right in the reversed direction
+        parabola.reverse();
+        assert!((parabola.curvature(0.5) + 2.0).abs() < 1e-12);
+        let straight = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(3.0, 3.0),
+        ]);
+        assert_eq!(straight.curvature(0.3), 0.0);
+        assert!(straight.radius_of_curvature(0.3).is_infinite());
+    }
+
+    #[test]
+    fn test_inflections() {
+        // symmetric s-curve turning right and then left
+        let mut s = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, -1.0),
+            Point::new(3.0, 0.0),
+        ]);
+        let inflections = s.inflections();
+        assert_eq!(inflections.len(), 1);
+        assert!((inflections[0] - 0.5).abs() < 1e-9);
+        assert!(s.curvature(0.4) < 0.0 && s.curvature(0.6) > 0.0);
+        // out of the range
+        s.set_range(s.point_at(0.0), s.point_at(0.4));
+        assert!(s.inflections().is_empty());
+        // the arch turns right all the way
+        assert!(arch().inflections().is_empty());
+    }
+}
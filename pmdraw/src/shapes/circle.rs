@@ -1,6 +1,10 @@
 use std::f64::consts::PI;
 
-use super::point::Point;
+use pmmath::transform::Transform2;
+
+use super::{
+    ellipse::EllipticalArc, lengths_every, line::Line, point::Point, projection::Projection,
+};
 
 #[derive(Copy, Clone)]
 pub struct Circle {
@@ -9,6 +13,10 @@ pub struct Circle {
 }
 
 impl Circle {
+    pub fn mirror(&self, mirror_line: Line) -> Circle {
+        Circle::new(self.origin.mirror(mirror_line), self.r)
+    }
+
     pub fn new(origin: Point, r: f64) -> Circle {
         Circle { origin, r }
     }
@@ -18,7 +26,62 @@ impl Circle {
         self.origin + (self.r * Point::new(theta.cos(), theta.sin()))
     }
 
+    /// Point at the length along the circumference counterclockwise from the angle 0
+    pub fn point_at_length(&self, length: f64) -> Point {
+        self.point_at((length / self.r).to_degrees())
+    }
+
+    /// Points at even intervals of length along the circumference from the angle 0
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(2.0 * PI * self.r, interval)
+            .into_iter()
+            .map(|length| self.point_at_length(length))
+            .collect()
+    }
+
+    /// Nearest point on the circumference to p, where t is the angle in degrees
+    ///
+    /// every point is equally near if p is the center, and the point at 0 degrees is taken then
+    pub fn project(&self, p: Point) -> Projection {
+        let v = p - self.origin;
+        let t = if v.x == 0.0 && v.y == 0.0 {
+            0.0
+        } else {
+            v.y.atan2(v.x) * 180.0 / PI
+        };
+        Projection::new(t, self.point_at(t), p)
+    }
+
+    /// Rotate around point
+    pub fn rotate(&mut self, angle_degree: f64, around: Point) {
+        self.origin.rotate(angle_degree, around);
+    }
+
+    /// Unit tangent in the counterclockwise direction at the length from the angle 0
+    pub fn tangent_at_length(&self, length: f64) -> Point {
+        let theta = length / self.r;
+        Point::new(-theta.sin(), theta.cos())
+    }
+
     pub fn to(&mut self, dx: f64, dy: f64) {
         self.origin = self.origin.to(dx, dy);
     }
+
+    /// Whole ellipse of the same points, which starts at the angle 0 like the circle
+    pub fn to_elliptical_arc(&self) -> EllipticalArc {
+        EllipticalArc::ellipse(self.origin, self.r, self.r, 0.0)
+    }
+
+    /// Transform by a transformation which keeps the circle round, such as rotation, mirror, and uniform scaling
+    ///
+    /// panics if the transformation stretches or shears the circle into an ellipse.
+    /// transform `to_elliptical_arc` instead, as `Shape` does
+    pub fn transform(&mut self, m: &Transform2) {
+        assert!(
+            m.is_similarity(),
+            "A circle cannot be stretched or sheared, transform it as an elliptical arc"
+        );
+        self.origin = self.origin.transform(m);
+        self.r *= m.determinant().abs().sqrt();
+    }
 }
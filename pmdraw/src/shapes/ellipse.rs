@@ -0,0 +1,434 @@
+use pmmath::{
+    quadrature::{integrate, inverse_arc_length},
+    roots::newton_bracketed,
+    transform::Transform2,
+};
+
+use super::{
+    arc::Arc,
+    bezier::{Bezier, MAX_ITERATIONS, TOLERANCE},
+    circle::Circle,
+    lengths_every,
+    line::Line,
+    offset,
+    point::Point,
+    projection::Projection,
+};
+
+/// number of intervals of the arc where the nearest point is looked for
+const PROJECTION_SAMPLES: usize = 32;
+
+/// Part of an ellipse from the start angle, going counterclockwise if the sweep angle is positive and clockwise if negative
+///
+/// angles are not the polar angles of points but the parametric angles theta of
+///  P(theta) = origin + rotate(rotation) (rx cos(theta), ry sin(theta))
+/// which are the parameter t of an elliptical arc in degrees. a whole ellipse is an elliptical arc of 360 degrees
+#[derive(Copy, Clone)]
+pub struct EllipticalArc {
+    /// center of the ellipse
+    pub origin: Point,
+    /// radius along the axis of the rotation
+    pub rx: f64,
+    /// radius perpendicular to the axis of the rotation
+    pub ry: f64,
+    /// angle of the x axis of the ellipse in degrees
+    pub rotation: f64,
+    pub start_angle: f64,
+    pub sweep_angle: f64,
+}
+
+impl EllipticalArc {
+    /// Derivative of the point by the angle in radians
+    pub fn derivative(&self, angle_degree: f64) -> Point {
+        let theta = angle_degree.to_radians();
+        let mut d = Point::new(-self.rx * theta.sin(), self.ry * theta.cos());
+        d.rotate(self.rotation, Point::new(0.0, 0.0));
+        d
+    }
+
+    /// Whole ellipse, which starts at the end of the x axis of the ellipse
+    pub fn ellipse(origin: Point, rx: f64, ry: f64, rotation: f64) -> EllipticalArc {
+        EllipticalArc::new(origin, rx, ry, rotation, 0.0, 360.0)
+    }
+
+    pub fn end(&self) -> Point {
+        self.point_at(self.start_angle + self.sweep_angle)
+    }
+
+    /// Length of the arc, which is integrated numerically since it has no closed form
+    pub fn len(&self) -> f64 {
+        integrate(
+            &|s| self.speed(s),
+            0.0,
+            self.sweep_angle.abs().to_radians(),
+            TOLERANCE,
+        )
+    }
+
+    pub fn mirror(&self, mirror_line: Line) -> EllipticalArc {
+        let mut e = *self;
+        e.transform(&Transform2::mirror(
+            mirror_line.origin.x,
+            mirror_line.origin.y,
+            mirror_line.end.x,
+            mirror_line.end.y,
+        ));
+        e
+    }
+
+    /// - rotation - angle of the x axis of the ellipse in degrees
+    /// - start_angle - parametric angle of the start point in degrees
+    /// - sweep_angle - parametric angle from the start point to the end point in degrees, which is counterclockwise if positive
+    pub fn new(
+        origin: Point,
+        rx: f64,
+        ry: f64,
+        rotation: f64,
+        start_angle: f64,
+        sweep_angle: f64,
+    ) -> EllipticalArc {
+        EllipticalArc {
+            origin,
+            rx,
+            ry,
+            rotation,
+            start_angle,
+            sweep_angle,
+        }
+    }
+
+    /// Curves at the distance from the arc within the tolerance
+    ///
+    /// the offset of an ellipse is not an ellipse, thus it is approximated by cubic curves like `Bezier::offset`.
+    /// positive distance is on the right side of the direction of the arc, and negative is on the left
+    pub fn offset(&self, distance: f64, tolerance: f64) -> Vec<Bezier> {
+        offset::offset(&self.to_beziers(tolerance / 2.0), distance, tolerance / 2.0)
+    }
+
+    pub fn point_at(&self, angle_degree: f64) -> Point {
+        let theta = angle_degree.to_radians();
+        let mut p = Point::new(self.rx * theta.cos(), self.ry * theta.sin());
+        p.rotate(self.rotation, Point::new(0.0, 0.0));
+        self.origin + p
+    }
+
+    /// Point at the length along the arc from the start
+    pub fn point_at_length(&self, length: f64) -> Point {
+        self.point_at(self.t_at_length(length))
+    }
+
+    /// Points at even intervals of length along the arc from the start
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(self.len(), interval)
+            .into_iter()
+            .map(|length| self.point_at_length(length))
+            .collect()
+    }
+
+    /// Nearest point on the arc to p, where t is the parametric angle in degrees
+    ///
+    /// the distance is the smallest at an edge of the arc or where (P - p) . dP/dtheta = 0,
+    /// whose roots are bracketed by samples along the arc and then solved by newton's method
+    pub fn project(&self, p: Point) -> Projection {
+        let sweep = self.sweep_angle.abs().to_radians();
+        let angle_at = |s: f64| self.start_angle + self.direction() * s.to_degrees();
+        // (P - p) . dP/ds and its derivative by the angle s along the arc
+        let g = |s: f64| {
+            let angle = angle_at(s);
+            let (v, d) = (self.point_at(angle) - p, self.derivative(angle));
+            self.direction() * (v.x * d.x + v.y * d.y)
+        };
+        let dg = |s: f64| {
+            let angle = angle_at(s);
+            let (v, d) = (self.point_at(angle) - p, self.derivative(angle));
+            // the second derivative of an ellipse points to the center
+            let d2 = self.origin - self.point_at(angle);
+            d.x * d.x + d.y * d.y + v.x * d2.x + v.y * d2.y
+        };
+        let tolerance = TOLERANCE * 1e-3 / self.rx.max(self.ry);
+        let mut candidates = vec![0.0, sweep];
+        for k in 0..PROJECTION_SAMPLES {
+            let (a, b) = (
+                sweep * k as f64 / PROJECTION_SAMPLES as f64,
+                sweep * (k + 1) as f64 / PROJECTION_SAMPLES as f64,
+            );
+            if g(a) * g(b) <= 0.0 {
+                if let Ok(root) = newton_bracketed(g, dg, a, b, tolerance, MAX_ITERATIONS) {
+                    candidates.push(root.x);
+                }
+            }
+        }
+        Projection::nearest(candidates.into_iter().map(|s| {
+            let angle = angle_at(s);
+            Projection::new(angle, self.point_at(angle), p)
+        }))
+        .expect("Edges of the arc are always candidates")
+    }
+
+    /// Swap the start and the end
+    pub fn reverse(&mut self) {
+        self.start_angle += self.sweep_angle;
+        self.sweep_angle = -self.sweep_angle;
+    }
+
+    /// Rotate around point
+    pub fn rotate(&mut self, angle_degree: f64, around: Point) {
+        self.transform(&Transform2::rotate_around(angle_degree, around.x, around.y));
+    }
+
+    /// Split the arc at the nearest point to p
+    pub fn split(&self, p: Point) -> Split {
+        self.split_at_t(self.project(p).t)
+    }
+
+    /// Split the arc at the length from the start
+    pub fn split_at_length(&self, length: f64) -> Split {
+        self.split_at_t(self.t_at_length(length))
+    }
+
+    /// Split the arc at the parametric angle in degrees
+    pub fn split_at_t(&self, angle_degree: f64) -> Split {
+        let end_angle = self.start_angle + self.sweep_angle;
+        Split {
+            fst: EllipticalArc {
+                sweep_angle: angle_degree - self.start_angle,
+                ..*self
+            },
+            snd: EllipticalArc {
+                start_angle: angle_degree,
+                sweep_angle: end_angle - angle_degree,
+                ..*self
+            },
+        }
+    }
+
+    pub fn start(&self) -> Point {
+        self.point_at(self.start_angle)
+    }
+
+    /// Parametric angle in degrees of the point at the length along the arc from the start
+    ///
+    /// negative length is the start, and panics if the length exceeds the arc
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        let (sweep, len) = (self.sweep_angle.abs().to_radians(), self.len());
+        assert!(length <= len + TOLERANCE, "Length is out of the arc");
+        let s = if length >= len {
+            sweep
+        } else {
+            inverse_arc_length(&|s| self.speed(s), 0.0, sweep, length, TOLERANCE)
+                .expect("Length is out of the arc")
+        };
+        self.start_angle + self.direction() * s.to_degrees()
+    }
+
+    /// Unit tangent at the parametric angle in the direction of the arc
+    pub fn tangent(&self, angle_degree: f64) -> Point {
+        self.direction() * self.derivative(angle_degree).normalize()
+    }
+
+    /// Unit tangent at the length along the arc from the start
+    pub fn tangent_at_length(&self, length: f64) -> Point {
+        self.tangent(self.t_at_length(length))
+    }
+
+    pub fn to(&mut self, dx: f64, dy: f64) {
+        self.origin = self.origin.to(dx, dy);
+    }
+
+    /// Cubic curves which approximate the arc within the tolerance
+    ///
+    /// they are cubic approximations of the circular arc which is transformed into the elliptical arc
+    pub fn to_beziers(&self, tolerance: f64) -> Vec<Bezier> {
+        let (circle, m) = self.circle_to_ellipse();
+        Arc::new(circle.origin, circle.r, self.start_angle, self.sweep_angle)
+            .to_beziers(tolerance)
+            .into_iter()
+            .map(|mut b| {
+                b.transform(&m);
+                b
+            })
+            .collect()
+    }
+
+    /// Transform by an affine transformation, which is exact for any transformation
+    ///
+    /// the axes of the transformed ellipse are found by the singular value decomposition
+    ///  A = rotate(theta) diag(sx, sy) rotate(phi)
+    /// of the linear part A of the transformation applied to the unit circle
+    pub fn transform(&mut self, m: &Transform2) {
+        let [[a, b, _], [d, e, _]] = m.rows;
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        // A = M rotate(rotation) diag(rx, ry)
+        let (a00, a01) = (
+            (a * cos + b * sin) * self.rx,
+            (-a * sin + b * cos) * self.ry,
+        );
+        let (a10, a11) = (
+            (d * cos + e * sin) * self.rx,
+            (-d * sin + e * cos) * self.ry,
+        );
+        // A is the sum of a scaled rotation and a scaled reflection
+        let (rotation_cos, rotation_sin) = ((a00 + a11) / 2.0, (a10 - a01) / 2.0);
+        let (reflection_cos, reflection_sin) = ((a00 - a11) / 2.0, (a10 + a01) / 2.0);
+        let q = (rotation_cos * rotation_cos + rotation_sin * rotation_sin).sqrt();
+        let r = (reflection_cos * reflection_cos + reflection_sin * reflection_sin).sqrt();
+        let (a1, a2) = (
+            reflection_sin.atan2(reflection_cos),
+            rotation_sin.atan2(rotation_cos),
+        );
+        let (theta, phi) = ((a2 + a1) / 2.0, (a2 - a1) / 2.0);
+        let (sx, sy) = (q + r, q - r);
+        self.origin = self.origin.transform(m);
+        self.rx = sx;
+        self.ry = sy.abs();
+        self.rotation = theta.to_degrees();
+        if sy < 0.0 {
+            // diag(1, -1) mirrors the parametric angle
+            self.start_angle = -self.start_angle - phi.to_degrees();
+            self.sweep_angle = -self.sweep_angle;
+        } else {
+            self.start_angle += phi.to_degrees();
+        }
+    }
+
+    /// 1 if the arc goes counterclockwise, or -1 if clockwise
+    fn direction(&self) -> f64 {
+        if self.sweep_angle < 0.0 {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Circle around (0, 0) of the larger radius, and the transformation which maps it onto the ellipse
+    ///
+    /// the point at each angle on the circle is mapped to the point at the same parametric angle on the ellipse,
+    /// and distances never grow by the transformation
+    pub(crate) fn circle_to_ellipse(&self) -> (Circle, Transform2) {
+        let r = self.rx.max(self.ry);
+        let m = Transform2::scale(self.rx / r, self.ry / r)
+            .then(&Transform2::rotate(self.rotation))
+            .then(&Transform2::translate(self.origin.x, self.origin.y));
+        (Circle::new(Point::new(0.0, 0.0), r), m)
+    }
+
+    /// Speed of the point by the angle s in radians from the start
+    fn speed(&self, s: f64) -> f64 {
+        self.derivative(self.start_angle + self.direction() * s.to_degrees())
+            .norm()
+    }
+}
+
+pub struct Split {
+    pub fst: EllipticalArc,
+    pub snd: EllipticalArc,
+}
+
+#[cfg(test)]
+mod test_ellipse {
+    use std::f64::consts::PI;
+
+    use pmmath::transform::Transform2;
+
+    use crate::shapes::{bezier::TOLERANCE, ellipse::EllipticalArc, point::Point};
+
+    fn arc() -> EllipticalArc {
+        EllipticalArc::new(Point::new(1.0, 2.0), 3.0, 1.0, 30.0, 10.0, 100.0)
+    }
+
+    /// points of the arc transformed by m are the points of the transformed arc at the same proportion of the sweep
+    fn assert_transform(e: &EllipticalArc, m: &Transform2) {
+        let mut transformed = *e;
+        transformed.transform(m);
+        for k in 0..=8 {
+            let at = |e: &EllipticalArc| e.point_at(e.start_angle + e.sweep_angle * k as f64 / 8.0);
+            assert!(at(e).transform(m).distance(at(&transformed)) < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_transform() {
+        let e = arc();
+        // mirror, where the smaller singular value is negative
+        assert_transform(&e, &Transform2::mirror(0.0, 0.0, 1.0, 0.0));
+        assert_transform(&e, &Transform2::mirror(-1.0, 3.0, 2.0, 1.0));
+        assert_transform(&e, &Transform2::shear(0.5, 0.0));
+        assert_transform(
+            &e,
+            &Transform2::shear(0.3, -0.7).then(&Transform2::scale(2.0, -1.0)),
+        );
+        // a circle stretched into an ellipse
+        let circle = EllipticalArc::ellipse(Point::new(0.0, 0.0), 1.0, 1.0, 0.0);
+        assert_transform(
+            &circle,
+            &Transform2::scale(2.0, 1.0).then(&Transform2::rotate(45.0)),
+        );
+        // a mirror keeps the radii and reverses the direction
+        let mut mirrored = e;
+        mirrored.transform(&Transform2::mirror(0.0, 0.0, 0.0, 1.0));
+        assert!((mirrored.rx - 3.0).abs() < 1e-12 && (mirrored.ry - 1.0).abs() < 1e-12);
+        assert!(mirrored.sweep_angle == -100.0);
+    }
+
+    #[test]
+    fn test_len() {
+        // a circle, whose length is in a closed form
+        let circle = EllipticalArc::new(Point::new(1.0, 1.0), 2.0, 2.0, 15.0, 30.0, 90.0);
+        assert!((circle.len() - PI).abs() <= TOLERANCE);
+        assert!(
+            (EllipticalArc::ellipse(Point::new(0.0, 0.0), 2.0, 2.0, 0.0).len() - 4.0 * PI).abs()
+                <= TOLERANCE
+        );
+        // quarter of an ellipse is longer than the chord and shorter than the two radii
+        let quarter = EllipticalArc::new(Point::new(0.0, 0.0), 3.0, 1.0, 0.0, 0.0, 90.0);
+        assert!(10.0_f64.sqrt() < quarter.len() && quarter.len() < 4.0);
+        let length = quarter.split_at_t(45.0).fst.len();
+        assert!((quarter.t_at_length(length) - 45.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_t_at_length_out_of_range() {
+        let e = arc();
+        assert_eq!(e.t_at_length(-1.0), 10.0);
+        assert_eq!(e.t_at_length(e.len() + TOLERANCE / 2.0), 110.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Length is out of the arc")]
+    fn test_t_at_length_beyond_end() {
+        let e = arc();
+        e.t_at_length(e.len() + 1.0);
+    }
+
+    #[test]
+    fn test_to_beziers() {
+        let e = arc();
+        for tolerance in [0.1, 0.001, 0.00001].iter() {
+            let beziers = e.to_beziers(*tolerance);
+            assert!(beziers[0].origin().distance(e.start()) < 1e-12);
+            assert!(beziers[beziers.len() - 1].end().distance(e.end()) < 1e-12);
+            for b in beziers.iter() {
+                for k in 0..=50 {
+                    assert!(e.project(b.point_at(k as f64 / 50.0)).distance <= *tolerance);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_project() {
+        let e = EllipticalArc::new(Point::new(0.0, 0.0), 2.0, 1.0, 0.0, 0.0, 90.0);
+        // inside the sweep, on the normal at the parametric angle 45 degrees
+        let p = e.point_at(45.0);
+        let normal = Point::new(p.x / 4.0, p.y).normalize();
+        let projection = e.project(p + 0.5 * normal);
+        assert!((projection.t - 45.0).abs() < 1e-6 && (projection.distance - 0.5).abs() < 1e-9);
+        // beyond the edges, the edges are the nearest
+        assert_eq!(e.project(Point::new(3.0, -0.5)).t, 0.0);
+        assert_eq!(e.project(Point::new(-0.5, 2.0)).t, 90.0);
+        let mut clockwise = e;
+        clockwise.reverse();
+        assert_eq!(clockwise.project(Point::new(3.0, -0.5)).t, 0.0);
+        assert_eq!(clockwise.project(Point::new(-0.5, 2.0)).t, 90.0);
+    }
+}
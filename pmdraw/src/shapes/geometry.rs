@@ -0,0 +1,820 @@
+use std::f64::consts::PI;
+
+use pmmath::transform::Transform2;
+
+use crate::flatten::{
+    flatten_arc, flatten_bezier, flatten_circle, flatten_elliptical_arc, flatten_spline,
+};
+
+use super::{
+    arc::{angle_in_sweep, Arc},
+    bezier::Bezier,
+    circle::Circle,
+    ellipse::EllipticalArc,
+    line::Line,
+    point::Point,
+    projection::Projection,
+    spline::Spline,
+    Shape,
+};
+
+/// radius of the circle marking a point in centimeters
+pub const POINT_RADIUS: f64 = 1.0;
+
+/// What every shape can do, thus exporters and patterns handle any shape without knowing which one it is
+///
+/// the parameter t of `split` is what `project` of the shape gives
+pub trait Geometry {
+    /// Axis-aligned rectangle containing the shape
+    fn bounding_box(&self) -> BoundingBox;
+    fn end(&self) -> Point;
+    /// Points along the shape, where chords between them are within the tolerance from the shape
+    ///
+    /// - tolerance - distance allowed between the shape and its chords in centimeters
+    fn flatten(&self, tolerance: f64) -> Vec<Point>;
+    fn length(&self) -> f64;
+    /// Point at the parameter t
+    fn point_at(&self, t: f64) -> Point;
+    /// Point at the length along the shape from the start
+    fn point_at_length(&self, length: f64) -> Point;
+    /// Nearest point on the shape to p
+    fn project(&self, p: Point) -> Projection;
+    /// The shape going from the end to the start, which is another kind of shape if the shape cannot go the other way
+    fn reversed(&self) -> Shape;
+    /// Pieces of the shape cut at t, which are two for open shapes and one for closed shapes cut open at t
+    fn split(&self, t: f64) -> Vec<Shape>;
+    fn start(&self) -> Point;
+    /// Points along which the shape is drawn, which are what `flatten` gives unless the shape has no length to draw
+    fn stroke(&self, tolerance: f64) -> Vec<Point> {
+        self.flatten(tolerance)
+    }
+    /// Part of the shape from t0 to t1
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape;
+    /// Parameter of the point at the length along the shape from the start
+    fn t_at_length(&self, length: f64) -> f64;
+    /// Parameters at the start and the end
+    fn t_range(&self) -> (f64, f64);
+    /// Unit tangent at the parameter t in the direction from the start to the end
+    fn tangent(&self, t: f64) -> Point;
+    /// The shape transformed by an affine transformation, which is another kind of shape if the shape cannot keep its kind
+    fn transformed(&self, m: &Transform2) -> Shape;
+}
+
+/// Axis-aligned rectangle
+#[derive(Copy, Clone)]
+pub struct BoundingBox {
+    /// corner of the smallest x and y
+    pub min: Point,
+    /// corner of the largest x and y
+    pub max: Point,
+}
+
+impl BoundingBox {
+    /// The smallest box containing all the points
+    ///
+    /// panics if there are no points
+    pub fn from_points(points: &[Point]) -> BoundingBox {
+        assert!(!points.is_empty(), "A bounding box needs a point at least");
+        points.iter().skip(1).fold(
+            BoundingBox {
+                min: points[0],
+                max: points[0],
+            },
+            |bounding_box, p| bounding_box.union(&BoundingBox { min: *p, max: *p }),
+        )
+    }
+
+    /// true if the boxes share any point, including their edges
+    pub fn overlaps(&self, another: &BoundingBox) -> bool {
+        self.min.x <= another.max.x
+            && another.min.x <= self.max.x
+            && self.min.y <= another.max.y
+            && another.min.y <= self.max.y
+    }
+
+    /// The smallest box containing both boxes
+    pub fn union(&self, another: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Point::new(self.min.x.min(another.min.x), self.min.y.min(another.min.y)),
+            max: Point::new(self.max.x.max(another.max.x), self.max.y.max(another.max.y)),
+        }
+    }
+}
+
+impl Geometry for Arc {
+    /// exact box of the edges and the points at multiples of 90 degrees in the sweep
+    fn bounding_box(&self) -> BoundingBox {
+        let mut points = vec![Arc::start(self), Arc::end(self)];
+        points.extend(
+            [0.0, 90.0, 180.0, 270.0]
+                .iter()
+                .filter_map(|angle| angle_in_sweep(*angle, self.start_angle, self.sweep_angle, 0.0))
+                .map(|angle| self.point_at(angle)),
+        );
+        BoundingBox::from_points(&points)
+    }
+
+    fn end(&self) -> Point {
+        Arc::end(self)
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        flatten_arc(self, tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        self.len()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        Arc::point_at(self, t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        Arc::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Arc::project(self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        let mut reversed = *self;
+        reversed.reverse();
+        Shape::Arc(reversed)
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        let split = self.split_at_t(t);
+        vec![Shape::Arc(split.fst), Shape::Arc(split.snd)]
+    }
+
+    fn start(&self) -> Point {
+        Arc::start(self)
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::Arc(Arc::new(self.origin, self.r, t0, t1 - t0))
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        Arc::t_at_length(self, length)
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (self.start_angle, self.start_angle + self.sweep_angle)
+    }
+
+    fn tangent(&self, t: f64) -> Point {
+        Arc::tangent(self, t)
+    }
+
+    /// an elliptical arc if the transformation does not keep the arc round
+    fn transformed(&self, m: &Transform2) -> Shape {
+        if !m.is_similarity() {
+            return self.to_elliptical_arc().transformed(m);
+        }
+        let mut transformed = *self;
+        transformed.transform(m);
+        Shape::Arc(transformed)
+    }
+}
+
+impl Geometry for Bezier {
+    /// exact box of the edges of the range and the points where x or y is locally minimum or maximum
+    fn bounding_box(&self) -> BoundingBox {
+        let range = self.range();
+        let mut points = vec![range.from, range.to];
+        points.extend(self.extrema().into_iter().map(|t| self.point_at(t)));
+        BoundingBox::from_points(&points)
+    }
+
+    fn end(&self) -> Point {
+        self.range().to
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        flatten_bezier(self, tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        self.len()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        Bezier::point_at(self, t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        Bezier::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Bezier::project(self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        let mut reversed = self.clone();
+        reversed.reverse();
+        Shape::Bezier(reversed)
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        let split = self.split_at_t(t);
+        vec![Shape::Bezier(split.fst), Shape::Bezier(split.snd)]
+    }
+
+    fn start(&self) -> Point {
+        self.range().from
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::Bezier(self.sub_curve(t0, t1))
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        Bezier::t_at_length(self, length)
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        let range = Bezier::t_range(self);
+        (range.from, range.to)
+    }
+
+    /// the tangent of the curve turned around if its range goes backwards
+    fn tangent(&self, t: f64) -> Point {
+        let (from, to) = Geometry::t_range(self);
+        let direction = if from <= to { 1.0 } else { -1.0 };
+        direction * Bezier::tangent(self, t)
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        let mut transformed = self.clone();
+        transformed.transform(m);
+        Shape::Bezier(transformed)
+    }
+}
+
+/// a circle goes counterclockwise from the angle 0, where it starts and ends
+impl Geometry for Circle {
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: self.origin.to(-self.r, -self.r),
+            max: self.origin.to(self.r, self.r),
+        }
+    }
+
+    fn end(&self) -> Point {
+        self.point_at(0.0)
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        flatten_circle(self, tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        2.0 * PI * self.r
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        Circle::point_at(self, t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        Circle::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Circle::project(self, p)
+    }
+
+    /// the arc going around clockwise, since a circle always goes counterclockwise
+    fn reversed(&self) -> Shape {
+        Arc::new(self.origin, self.r, 0.0, 360.0).reversed()
+    }
+
+    /// the arc going around from t
+    fn split(&self, t: f64) -> Vec<Shape> {
+        vec![Shape::Arc(Arc::new(self.origin, self.r, t, 360.0))]
+    }
+
+    fn start(&self) -> Point {
+        self.point_at(0.0)
+    }
+
+    /// the arc of the circle from t0 to t1
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::Arc(Arc::new(self.origin, self.r, t0, t1 - t0))
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        (length / self.r).to_degrees()
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (0.0, 360.0)
+    }
+
+    fn tangent(&self, t: f64) -> Point {
+        let theta = t.to_radians();
+        Point::new(-theta.sin(), theta.cos())
+    }
+
+    /// an elliptical arc around the whole ellipse if the transformation does not keep the circle round
+    fn transformed(&self, m: &Transform2) -> Shape {
+        if !m.is_similarity() {
+            return self.to_elliptical_arc().transformed(m);
+        }
+        let mut transformed = *self;
+        transformed.transform(m);
+        Shape::Circle(transformed)
+    }
+}
+
+impl Geometry for EllipticalArc {
+    /// exact box of the edges and the points in the sweep where x or y of the ellipse is minimum or maximum
+    fn bounding_box(&self) -> BoundingBox {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        // dx/dtheta = 0 and dy/dtheta = 0 at these angles and the opposite ones
+        let x_extreme = (-self.ry * sin).atan2(self.rx * cos).to_degrees();
+        let y_extreme = (self.ry * cos).atan2(self.rx * sin).to_degrees();
+        let mut points = vec![EllipticalArc::start(self), EllipticalArc::end(self)];
+        points.extend(
+            [x_extreme, x_extreme + 180.0, y_extreme, y_extreme + 180.0]
+                .iter()
+                .filter_map(|angle| angle_in_sweep(*angle, self.start_angle, self.sweep_angle, 0.0))
+                .map(|angle| self.point_at(angle)),
+        );
+        BoundingBox::from_points(&points)
+    }
+
+    fn end(&self) -> Point {
+        EllipticalArc::end(self)
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        flatten_elliptical_arc(self, tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        self.len()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        EllipticalArc::point_at(self, t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        EllipticalArc::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        EllipticalArc::project(self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        let mut reversed = *self;
+        reversed.reverse();
+        Shape::EllipticalArc(reversed)
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        let split = self.split_at_t(t);
+        vec![
+            Shape::EllipticalArc(split.fst),
+            Shape::EllipticalArc(split.snd),
+        ]
+    }
+
+    fn start(&self) -> Point {
+        EllipticalArc::start(self)
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::EllipticalArc(EllipticalArc {
+            start_angle: t0,
+            sweep_angle: t1 - t0,
+            ..*self
+        })
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        EllipticalArc::t_at_length(self, length)
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (self.start_angle, self.start_angle + self.sweep_angle)
+    }
+
+    fn tangent(&self, t: f64) -> Point {
+        EllipticalArc::tangent(self, t)
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        let mut transformed = *self;
+        transformed.transform(m);
+        Shape::EllipticalArc(transformed)
+    }
+}
+
+impl Geometry for Line {
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_points(&[self.origin, self.end])
+    }
+
+    fn end(&self) -> Point {
+        self.end
+    }
+
+    fn flatten(&self, _tolerance: f64) -> Vec<Point> {
+        vec![self.origin, self.end]
+    }
+
+    fn length(&self) -> f64 {
+        self.len()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        self.between(t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        Line::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Line::project(self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        let mut reversed = *self;
+        reversed.reverse();
+        Shape::Line(reversed)
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        let split_point = self.between(t);
+        vec![
+            Shape::Line(Line::new(self.origin, split_point)),
+            Shape::Line(Line::new(split_point, self.end)),
+        ]
+    }
+
+    fn start(&self) -> Point {
+        self.origin
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::Line(Line::new(self.between(t0), self.between(t1)))
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        length / self.len()
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn tangent(&self, _t: f64) -> Point {
+        self.vec().normalize()
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        let mut transformed = *self;
+        transformed.transform(m);
+        Shape::Line(transformed)
+    }
+}
+
+/// a point is a shape of no length which starts and ends at itself
+impl Geometry for Point {
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            min: *self,
+            max: *self,
+        }
+    }
+
+    fn end(&self) -> Point {
+        *self
+    }
+
+    fn flatten(&self, _tolerance: f64) -> Vec<Point> {
+        vec![*self]
+    }
+
+    fn length(&self) -> f64 {
+        0.0
+    }
+
+    fn point_at(&self, _t: f64) -> Point {
+        *self
+    }
+
+    fn point_at_length(&self, _length: f64) -> Point {
+        *self
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Projection::new(0.0, *self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        Shape::Point(*self)
+    }
+
+    fn split(&self, _t: f64) -> Vec<Shape> {
+        vec![Shape::Point(*self)]
+    }
+
+    fn start(&self) -> Point {
+        *self
+    }
+
+    /// a circle of `POINT_RADIUS` around the point
+    fn stroke(&self, tolerance: f64) -> Vec<Point> {
+        flatten_circle(&Circle::new(*self, POINT_RADIUS), tolerance)
+    }
+
+    fn sub_shape(&self, _t0: f64, _t1: f64) -> Shape {
+        Shape::Point(*self)
+    }
+
+    fn t_at_length(&self, _length: f64) -> f64 {
+        0.0
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+
+    /// a point has no direction, thus the tangent is zero
+    fn tangent(&self, _t: f64) -> Point {
+        Point::new(0.0, 0.0)
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        Shape::Point(Point::transform(self, m))
+    }
+}
+
+impl Geometry for Shape {
+    fn bounding_box(&self) -> BoundingBox {
+        self.geometry().bounding_box()
+    }
+
+    fn end(&self) -> Point {
+        self.geometry().end()
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        self.geometry().flatten(tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        self.geometry().length()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        self.geometry().point_at(t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        self.geometry().point_at_length(length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        self.geometry().project(p)
+    }
+
+    fn reversed(&self) -> Shape {
+        self.geometry().reversed()
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        self.geometry().split(t)
+    }
+
+    fn start(&self) -> Point {
+        self.geometry().start()
+    }
+
+    fn stroke(&self, tolerance: f64) -> Vec<Point> {
+        self.geometry().stroke(tolerance)
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        self.geometry().sub_shape(t0, t1)
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        self.geometry().t_at_length(length)
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        self.geometry().t_range()
+    }
+
+    fn tangent(&self, t: f64) -> Point {
+        self.geometry().tangent(t)
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        self.geometry().transformed(m)
+    }
+}
+
+impl Geometry for Spline {
+    /// exact box as the union of the boxes of segments
+    fn bounding_box(&self) -> BoundingBox {
+        let segments = self.segments();
+        segments
+            .iter()
+            .skip(1)
+            .fold(segments[0].bounding_box(), |union, b| {
+                union.union(&b.bounding_box())
+            })
+    }
+
+    fn end(&self) -> Point {
+        Spline::end(self)
+    }
+
+    fn flatten(&self, tolerance: f64) -> Vec<Point> {
+        flatten_spline(self, tolerance)
+    }
+
+    fn length(&self) -> f64 {
+        self.len()
+    }
+
+    fn point_at(&self, t: f64) -> Point {
+        Spline::point_at(self, t)
+    }
+
+    fn point_at_length(&self, length: f64) -> Point {
+        Spline::point_at_length(self, length)
+    }
+
+    fn project(&self, p: Point) -> Projection {
+        Spline::project(self, p)
+    }
+
+    fn reversed(&self) -> Shape {
+        let mut reversed = self.clone();
+        reversed.reverse();
+        Shape::Spline(reversed)
+    }
+
+    fn split(&self, t: f64) -> Vec<Shape> {
+        let split = self.split_at_t(t);
+        vec![Shape::Spline(split.fst), Shape::Spline(split.snd)]
+    }
+
+    fn start(&self) -> Point {
+        self.origin()
+    }
+
+    fn sub_shape(&self, t0: f64, t1: f64) -> Shape {
+        Shape::Spline(self.sub_curve(t0, t1))
+    }
+
+    fn t_at_length(&self, length: f64) -> f64 {
+        Spline::t_at_length(self, length)
+    }
+
+    fn t_range(&self) -> (f64, f64) {
+        (0.0, 1.0)
+    }
+
+    fn tangent(&self, t: f64) -> Point {
+        self.derivative(t).normalize()
+    }
+
+    fn transformed(&self, m: &Transform2) -> Shape {
+        let mut transformed = self.clone();
+        transformed.transform(m);
+        Shape::Spline(transformed)
+    }
+}
+
+#[cfg(test)]
+mod test_geometry {
+    use pmmath::transform::Transform2;
+
+    use crate::{
+        contour::{Contour, Segment},
+        shapes::{
+            arc::Arc, bezier::Bezier, circle::Circle, geometry::Geometry, point::Point, Shape,
+        },
+    };
+
+    fn area(shape: Shape) -> f64 {
+        Contour::new(vec![Segment::new(shape)]).area()
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        // the control points are far above the arch, whose top is at y = 2.25
+        let arch = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(5.0, 0.0),
+        ]);
+        let b = arch.bounding_box();
+        assert!(b.min.distance(Point::new(0.0, 0.0)) < 1e-9);
+        assert!(b.max.distance(Point::new(5.0, 2.25)) < 1e-9);
+        // the x of the s curve goes beyond its edges, but not as far as its control points
+        let s = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(9.0, 1.0),
+            Point::new(-7.0, 2.0),
+            Point::new(2.0, 3.0),
+        ]);
+        let b = s.bounding_box();
+        for k in 0..=100 {
+            let p = s.point_at(k as f64 / 100.0);
+            assert!(b.min.x <= p.x && p.x <= b.max.x && b.min.y <= p.y && p.y <= b.max.y);
+        }
+        assert!(b.min.x < 0.0 && b.max.x > 2.0 && b.max.x < 3.0);
+        // the top of the arc between the edges
+        let a = Arc::new(Point::new(0.0, 0.0), 2.0, 30.0, 120.0);
+        let b = a.bounding_box();
+        assert!((b.max.y - 2.0).abs() < 1e-9 && (b.min.y - 1.0).abs() < 1e-9);
+        assert!((b.max.x - 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reversed_circle() {
+        let c = Circle::new(Point::new(1.0, 2.0), 3.0);
+        let geometry: &dyn Geometry = &c;
+        let reversed = geometry.reversed();
+        assert!(matches!(reversed, Shape::Arc(_)));
+        // it goes around clockwise from the same point
+        assert!(reversed.start().distance(c.start()) < 1e-9);
+        assert!(reversed.end().distance(c.start()) < 1e-9);
+        let t = reversed.t_range().0;
+        assert!((reversed.tangent(t) + c.tangent(0.0)).norm() < 1e-9);
+        assert!((area(reversed) + area(Shape::Circle(c))).abs() < 1e-9);
+        assert!((area(Shape::Circle(c)) - 9.0 * std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_reversed_shape() {
+        let b = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(3.0, 0.0),
+        ]);
+        let shape = Shape::Bezier(b.clone());
+        let geometry: &dyn Geometry = &shape;
+        let reversed = geometry.reversed();
+        assert!(matches!(reversed, Shape::Bezier(_)));
+        assert!(reversed.start() == b.end() && reversed.end() == b.start());
+        // reversing a shape in place turns a circle into an arc too
+        let mut circle = Shape::Circle(Circle::new(Point::new(0.0, 0.0), 1.0));
+        circle.reverse();
+        assert!(matches!(circle, Shape::Arc(_)));
+    }
+
+    #[test]
+    fn test_transformed_circle() {
+        let c = Circle::new(Point::new(1.0, 2.0), 3.0);
+        let geometry: &dyn Geometry = &c;
+        // a similarity keeps the circle round
+        let rotated = geometry.transformed(&Transform2::rotate(30.0));
+        assert!(matches!(rotated, Shape::Circle(_)));
+        // a stretch makes an ellipse, which goes through the transformed points of the circle
+        let m = Transform2::scale(2.0, 1.0);
+        let stretched = geometry.transformed(&m);
+        assert!(matches!(stretched, Shape::EllipticalArc(_)));
+        for k in 0..8 {
+            let p = c.point_at(45.0 * k as f64).transform(&m);
+            assert!(stretched.project(p).distance < 1e-6);
+        }
+        assert!((area(stretched) - 18.0 * std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transformed_arc() {
+        let a = Arc::new(Point::new(0.0, 0.0), 2.0, 30.0, 120.0);
+        let geometry: &dyn Geometry = &a;
+        let m = Transform2::shear(0.5, 0.0);
+        let sheared = geometry.transformed(&m);
+        assert!(matches!(sheared, Shape::EllipticalArc(_)));
+        assert!(sheared.start().distance(a.start().transform(&m)) < 1e-9);
+        assert!(sheared.end().distance(a.end().transform(&m)) < 1e-9);
+        // within the precision of the projection
+        let (t0, t1) = a.t_range();
+        for k in 0..=8 {
+            let p = a.point_at(t0 + (t1 - t0) * k as f64 / 8.0).transform(&m);
+            assert!(sheared.project(p).distance < 1e-6);
+        }
+        // transforming a shape in place converts it too
+        let mut shape = Shape::Arc(a);
+        shape.transform(&m);
+        assert!(matches!(shape, Shape::EllipticalArc(_)));
+    }
+}
@@ -0,0 +1,1316 @@
+use pmmath::{polynomial::Polynomial, transform::Transform2};
+
+use super::{
+    arc::{angle_in_sweep, Arc},
+    bezier::{Bezier, RangeF64, TOLERANCE},
+    circle::Circle,
+    ellipse::EllipticalArc,
+    geometry::Geometry,
+    line::Line,
+    point::Point,
+    spline::Spline,
+    Shape,
+};
+
+/// shapes are tangent if the sine of the angle between their directions is smaller than this
+const TANGENT_TOLERANCE: f64 = 1e-3;
+/// give up subdividing curves after this number of halvings
+const MAX_DEPTH: usize = 32;
+/// number of points of a curve checked to decide whether it lies on another
+const COINCIDENCE_SAMPLES: usize = 8;
+
+/// How far lines extend when they intersect other shapes
+#[derive(Copy, Clone, PartialEq)]
+pub enum Extent {
+    /// only between the origin and the end
+    Segment,
+    /// infinitely beyond the origin and the end
+    Infinite,
+}
+
+/// How two shapes meet
+#[derive(Copy, Clone, PartialEq)]
+pub enum IntersectionKind {
+    /// shapes cross each other
+    Crossing,
+    /// shapes touch each other in the same direction
+    Tangent,
+}
+
+/// Point where two shapes meet
+#[derive(Copy, Clone)]
+pub struct Intersection {
+    pub point: Point,
+    /// parameter of the point on the first shape, which is what `point_at` or `between` of the shape takes
+    pub t1: f64,
+    /// parameter of the point on the second shape
+    pub t2: f64,
+    pub kind: IntersectionKind,
+}
+
+/// All points where two shapes meet
+#[derive(Clone)]
+pub enum Intersections {
+    /// intersections in ascending order of t1, which is empty if shapes never meet
+    Points(Vec<Intersection>),
+    /// shapes overlap each other, where they have infinitely many points in common
+    Coincident,
+    /// parts of shapes overlap each other between the parameters, where t1 is in ascending order
+    Overlapping { t1: RangeF64, t2: RangeF64 },
+}
+
+impl Intersections {
+    /// Intersections at isolated points, which are none if shapes are coincident
+    pub fn points(&self) -> Vec<Intersection> {
+        match self {
+            Intersections::Points(points) => points.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Replace each intersection, or drop it if it is replaced by none
+    fn filter_map<F>(self, f: F) -> Intersections
+    where
+        F: Fn(Intersection) -> Option<Intersection>,
+    {
+        match self {
+            Intersections::Points(points) => {
+                Intersections::Points(sorted(points.into_iter().filter_map(f).collect()))
+            }
+            intersections => intersections,
+        }
+    }
+
+    /// Swap the first and the second shape
+    fn swap(self) -> Intersections {
+        match self {
+            Intersections::Points(points) => Intersections::Points(sorted(
+                points
+                    .into_iter()
+                    .map(|i| Intersection {
+                        t1: i.t2,
+                        t2: i.t1,
+                        ..i
+                    })
+                    .collect(),
+            )),
+            Intersections::Coincident => Intersections::Coincident,
+            Intersections::Overlapping { t1, t2 } => overlapping(t2, t1),
+        }
+    }
+}
+
+/// Intersections of any shapes, where lines are segments
+pub fn intersect(s1: &Shape, s2: &Shape) -> Intersections {
+    let extent = Extent::Segment;
+    match (s1, s2) {
+        (Shape::Point(p), s) => point_shape(*p, s),
+        (s, Shape::Point(p)) => point_shape(*p, s).swap(),
+        (Shape::Arc(a), s) => arc_with(a, s),
+        (s, Shape::Arc(a)) => arc_with(a, s).swap(),
+        (Shape::EllipticalArc(e), s) => elliptical_arc_with(e, s),
+        (s, Shape::EllipticalArc(e)) => elliptical_arc_with(e, s).swap(),
+        (Shape::Line(l1), Shape::Line(l2)) => line_line(l1, l2, extent),
+        (Shape::Line(l), Shape::Circle(c)) => line_circle(l, c, extent),
+        (Shape::Line(l), Shape::Bezier(b)) => line_bezier(l, b, extent),
+        (Shape::Line(l), Shape::Spline(s)) => {
+            spline_with(s, |b| line_bezier(l, b, extent).swap()).swap()
+        }
+        (Shape::Circle(c), Shape::Line(l)) => line_circle(l, c, extent).swap(),
+        (Shape::Circle(c1), Shape::Circle(c2)) => circle_circle(c1, c2),
+        (Shape::Circle(c), Shape::Bezier(b)) => circle_bezier(c, b),
+        (Shape::Circle(c), Shape::Spline(s)) => {
+            spline_with(s, |b| circle_bezier(c, b).swap()).swap()
+        }
+        (Shape::Bezier(b), Shape::Line(l)) => line_bezier(l, b, extent).swap(),
+        (Shape::Bezier(b), Shape::Circle(c)) => circle_bezier(c, b).swap(),
+        (Shape::Bezier(b1), Shape::Bezier(b2)) => bezier_bezier(b1, b2),
+        (Shape::Bezier(b), Shape::Spline(s)) => spline_with(s, |b2| bezier_bezier(b2, b)).swap(),
+        (Shape::Spline(s), Shape::Line(l)) => spline_with(s, |b| line_bezier(l, b, extent).swap()),
+        (Shape::Spline(s), Shape::Circle(c)) => spline_with(s, |b| circle_bezier(c, b).swap()),
+        (Shape::Spline(s), Shape::Bezier(b)) => spline_with(s, |b1| bezier_bezier(b1, b)),
+        (Shape::Spline(s1), Shape::Spline(s2)) => {
+            spline_with(s1, |b1| spline_with(s2, |b2| bezier_bezier(b1, b2)).swap()).swap()
+        }
+    }
+}
+
+/// Intersections of two lines
+///
+/// lines on the same straight line are coincident if they overlap, and meet at a tangent point if they only touch at their edges
+/// - extent - whether lines are segments or infinite
+pub fn line_line(l1: &Line, l2: &Line, extent: Extent) -> Intersections {
+    let (a1, a2) = (l1.vec(), l2.vec());
+    let c = l2.origin - l1.origin;
+    let denominator = cross(a1, a2);
+    if denominator.abs() <= f64::EPSILON * a1.norm() * a2.norm() {
+        // parallel
+        if l1.project_infinite(l2.origin).distance > TOLERANCE {
+            return Intersections::Points(Vec::new());
+        }
+        if extent == Extent::Infinite {
+            return Intersections::Coincident;
+        }
+        let s0 = l1.project_infinite(l2.origin).t;
+        let s1 = l1.project_infinite(l2.end).t;
+        let (from, to) = (s0.min(s1).max(0.0), s0.max(s1).min(1.0));
+        let overlap = (to - from) * a1.norm();
+        if overlap > TOLERANCE {
+            return Intersections::Coincident;
+        }
+        if overlap < -TOLERANCE {
+            return Intersections::Points(Vec::new());
+        }
+        let point = l1.between(from.min(1.0));
+        return Intersections::Points(vec![Intersection {
+            point,
+            t1: from.min(1.0),
+            t2: l2.project(point).t,
+            kind: IntersectionKind::Tangent,
+        }]);
+    }
+    let t1 = cross(c, a2) / denominator;
+    let t2 = cross(c, a1) / denominator;
+    let on = |l: &Line, t: f64| on_line(l, t, extent);
+    Intersections::Points(match (on(l1, t1), on(l2, t2)) {
+        (Some(t1), Some(t2)) => vec![Intersection {
+            point: l1.between(t1),
+            t1,
+            t2,
+            kind: IntersectionKind::Crossing,
+        }],
+        _ => Vec::new(),
+    })
+}
+
+/// Intersections of a line and a circle, where t2 is the angle on the circle in degrees
+///
+/// a line without its length meets nothing
+/// - extent - whether the line is a segment or infinite
+pub fn line_circle(l: &Line, c: &Circle, extent: Extent) -> Intersections {
+    let a = l.vec();
+    if is_degenerate(l) {
+        return Intersections::Points(Vec::new());
+    }
+    let foot = l.project_infinite(c.origin);
+    let mut t = Vec::new();
+    if (foot.distance - c.r).abs() <= TOLERANCE {
+        t.push((foot.t, IntersectionKind::Tangent));
+    } else if foot.distance < c.r {
+        let half_chord = (c.r * c.r - foot.distance * foot.distance).sqrt() / a.norm();
+        t.push((foot.t - half_chord, IntersectionKind::Crossing));
+        t.push((foot.t + half_chord, IntersectionKind::Crossing));
+    }
+    Intersections::Points(
+        t.into_iter()
+            .filter_map(|(t, kind)| {
+                on_line(l, t, extent).map(|t1| {
+                    let point = l.between(t1);
+                    Intersection {
+                        point,
+                        t1,
+                        t2: c.project(point).t,
+                        kind,
+                    }
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Intersections of two circles, where t1 and t2 are angles in degrees
+pub fn circle_circle(c1: &Circle, c2: &Circle) -> Intersections {
+    let v = c2.origin - c1.origin;
+    let d = v.norm();
+    if d <= TOLERANCE {
+        return if (c1.r - c2.r).abs() <= TOLERANCE {
+            Intersections::Coincident
+        } else {
+            Intersections::Points(Vec::new())
+        };
+    }
+    if d > c1.r + c2.r + TOLERANCE || d < (c1.r - c2.r).abs() - TOLERANCE {
+        return Intersections::Points(Vec::new());
+    }
+    // distance from c1 to the chord of common points, and half of the chord
+    let a = (d * d + c1.r * c1.r - c2.r * c2.r) / (2.0 * d);
+    let h = (c1.r * c1.r - a * a).max(0.0).sqrt();
+    let u = v / d;
+    let normal = Point::new(-u.y, u.x);
+    let middle = c1.origin + a * u;
+    let is_tangent =
+        (d - (c1.r + c2.r)).abs() <= TOLERANCE || (d - (c1.r - c2.r).abs()).abs() <= TOLERANCE;
+    let points = if is_tangent {
+        vec![(middle, IntersectionKind::Tangent)]
+    } else {
+        vec![
+            (middle - h * normal, IntersectionKind::Crossing),
+            (middle + h * normal, IntersectionKind::Crossing),
+        ]
+    };
+    Intersections::Points(sorted(
+        points
+            .into_iter()
+            .map(|(point, kind)| Intersection {
+                point,
+                t1: c1.project(point).t,
+                t2: c2.project(point).t,
+                kind,
+            })
+            .collect(),
+    ))
+}
+
+/// Intersections of a line and a curve in its range
+///
+/// they are roots of the distance from the line to the curve, which is a polynomial of t.
+/// a curve on the line is coincident if it overlaps the line, and meets it at a tangent point if they only touch at their edges,
+/// and a line without its length meets nothing
+/// - extent - whether the line is a segment or infinite
+pub fn line_bezier(l: &Line, b: &Bezier, extent: Extent) -> Intersections {
+    let a = l.vec();
+    if is_degenerate(l) {
+        return Intersections::Points(Vec::new());
+    }
+    let (x_t, y_t) = b.polynomials();
+    let distance = (&x_t - Polynomial::new(vec![l.origin.x])) * -a.y
+        + (&y_t - Polynomial::new(vec![l.origin.y])) * a.x;
+    if is_zero(&distance, TOLERANCE * a.norm()) {
+        return if extent == Extent::Infinite {
+            Intersections::Coincident
+        } else {
+            line_bezier_on_line(l, b, &x_t, &y_t)
+        };
+    }
+    let touches = |t: &f64| distance.eval(*t).abs() <= TOLERANCE * a.norm();
+    Intersections::Points(sorted(
+        roots_or_touches(b, &distance, touches)
+            .into_iter()
+            .filter_map(|t2| {
+                let point = b.point_at(t2);
+                on_line(l, l.project_infinite(point).t, extent).map(|t1| Intersection {
+                    point,
+                    t1,
+                    t2,
+                    kind: kind(a, b.derivative(t2)),
+                })
+            })
+            .collect(),
+    ))
+}
+
+/// Intersections of a circle and a curve in its range, where t1 is the angle on the circle in degrees
+///
+/// they are roots of the squared distance from the center minus the squared radius, which is a polynomial of t
+pub fn circle_bezier(c: &Circle, b: &Bezier) -> Intersections {
+    let (x_t, y_t) = b.polynomials();
+    let dx = &x_t - Polynomial::new(vec![c.origin.x]);
+    let dy = &y_t - Polynomial::new(vec![c.origin.y]);
+    let power = &dx * &dx + &dy * &dy - Polynomial::new(vec![c.r * c.r]);
+    if is_zero(&power, 2.0 * c.r * TOLERANCE) {
+        return Intersections::Coincident;
+    }
+    let touches = |t: &f64| power.eval(*t).abs() <= 2.0 * c.r * TOLERANCE;
+    Intersections::Points(sorted(
+        roots_or_touches(b, &power, touches)
+            .into_iter()
+            .map(|t2| {
+                let point = b.point_at(t2);
+                let t1 = c.project(point).t;
+                let theta = t1.to_radians();
+                Intersection {
+                    point,
+                    t1,
+                    t2,
+                    kind: kind(Point::new(-theta.sin(), theta.cos()), b.derivative(t2)),
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// Intersections of two curves in their ranges
+///
+/// curves are halved until pieces whose control points overlap are smaller than the tolerance,
+/// and then the intersection of each piece is polished by newton's method.
+/// curves are coincident if either one lies on the other, and overlapping if only parts of them do
+pub fn bezier_bezier(b1: &Bezier, b2: &Bezier) -> Intersections {
+    if !overlaps(bounding_box(b1), bounding_box(b2)) {
+        return Intersections::Points(Vec::new());
+    }
+    if lies_on(b1, b2) || lies_on(b2, b1) {
+        return Intersections::Coincident;
+    }
+    if let Some(overlap) = common_part(b1, b2) {
+        return overlap;
+    }
+    let (range1, range2) = (b1.t_range(), b2.t_range());
+    let mut candidates = Vec::new();
+    subdivide(
+        &b1.sub_curve(range1.from, range1.to),
+        (range1.from, range1.to),
+        &b2.sub_curve(range2.from, range2.to),
+        (range2.from, range2.to),
+        0,
+        &mut candidates,
+    );
+    let bounds = |from: f64, to: f64| (from.min(to), from.max(to));
+    let (bounds1, bounds2) = (
+        bounds(range1.from, range1.to),
+        bounds(range2.from, range2.to),
+    );
+    let mut candidates = candidates
+        .into_iter()
+        .map(|(t1, t2)| polish(b1, b2, (t1, t2), bounds1, bounds2))
+        .collect::<Vec<(f64, f64)>>();
+    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    // curves near a tangent point stay close to each other between candidates,
+    // which are regarded as one intersection
+    let mut clusters: Vec<Vec<(f64, f64)>> = Vec::new();
+    for (t1, t2) in candidates {
+        match clusters.last_mut() {
+            Some(cluster)
+                if b2
+                    .project(b1.point_at((cluster[cluster.len() - 1].0 + t1) / 2.0))
+                    .distance
+                    <= 2.0 * TOLERANCE =>
+            {
+                cluster.push((t1, t2))
+            }
+            _ => clusters.push(vec![(t1, t2)]),
+        }
+    }
+    Intersections::Points(
+        clusters
+            .into_iter()
+            .map(|cluster| {
+                let (t1, t2) = cluster[cluster.len() / 2];
+                let spread = b1
+                    .point_at(cluster[0].0)
+                    .distance(b1.point_at(cluster[cluster.len() - 1].0));
+                Intersection {
+                    point: b1.point_at(t1),
+                    t1,
+                    t2,
+                    // crossing curves meet only around one point, unlike tangent curves
+                    kind: if spread > 10.0 * TOLERANCE {
+                        IntersectionKind::Tangent
+                    } else {
+                        kind(b1.derivative(t1), b2.derivative(t2))
+                    },
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Intersections of a line and an arc, where t2 is the angle on the arc in degrees
+///
+/// - extent - whether the line is a segment or infinite
+pub fn line_arc(l: &Line, a: &Arc, extent: Extent) -> Intersections {
+    line_circle(l, &a.circle(), extent)
+        .filter_map(|i| on_arc(a, i.t2).map(|t2| Intersection { t2, ..i }))
+}
+
+/// Intersections of a circle and an arc, where t1 and t2 are angles in degrees
+///
+/// they are coincident if the arc is on the circle
+pub fn circle_arc(c: &Circle, a: &Arc) -> Intersections {
+    circle_circle(c, &a.circle()).filter_map(|i| on_arc(a, i.t2).map(|t2| Intersection { t2, ..i }))
+}
+
+/// Intersections of two arcs, where t1 and t2 are angles in degrees
+///
+/// arcs on the same circle are coincident if they overlap, and meet at a tangent point if they only touch at their edges
+pub fn arc_arc(a1: &Arc, a2: &Arc) -> Intersections {
+    match circle_circle(&a1.circle(), &a2.circle()) {
+        Intersections::Coincident => on_same_curve(&Shape::Arc(*a1), &Shape::Arc(*a2)),
+        intersections => intersections.filter_map(|i| match (on_arc(a1, i.t1), on_arc(a2, i.t2)) {
+            (Some(t1), Some(t2)) => Some(Intersection { t1, t2, ..i }),
+            _ => None,
+        }),
+    }
+}
+
+/// Intersections of an arc and a curve in its range, where t1 is the angle on the arc in degrees
+pub fn arc_bezier(a: &Arc, b: &Bezier) -> Intersections {
+    match circle_bezier(&a.circle(), b) {
+        Intersections::Coincident => on_same_curve(&Shape::Arc(*a), &Shape::Bezier(b.clone())),
+        intersections => {
+            intersections.filter_map(|i| on_arc(a, i.t1).map(|t1| Intersection { t1, ..i }))
+        }
+    }
+}
+
+/// Intersections of a line and an elliptical arc, where t2 is the parametric angle on the arc in degrees
+///
+/// the ellipse is transformed into a circle together with the line, which keeps the parameters of both
+/// - extent - whether the line is a segment or infinite
+pub fn line_elliptical_arc(l: &Line, e: &EllipticalArc, extent: Extent) -> Intersections {
+    let (circle, m) = e.circle_to_ellipse();
+    let mut l_on_circle = *l;
+    l_on_circle.transform(&to_circle(&m));
+    line_circle(&l_on_circle, &circle, extent).filter_map(|i| {
+        on_elliptical_arc(e, i.t2).map(|t2| Intersection {
+            point: l.between(i.t1),
+            t2,
+            ..i
+        })
+    })
+}
+
+/// Intersections of an elliptical arc and a curve in its range, where t1 is the parametric angle on the arc in degrees
+///
+/// the ellipse is transformed into a circle together with the curve, which keeps the parameters of both
+pub fn elliptical_arc_bezier(e: &EllipticalArc, b: &Bezier) -> Intersections {
+    let (circle, m) = e.circle_to_ellipse();
+    let mut b_on_circle = b.clone();
+    b_on_circle.transform(&to_circle(&m));
+    match circle_bezier(&circle, &b_on_circle) {
+        Intersections::Coincident => {
+            on_same_curve(&Shape::EllipticalArc(*e), &Shape::Bezier(b.clone()))
+        }
+        intersections => intersections.filter_map(|i| {
+            on_elliptical_arc(e, i.t1).map(|t1| Intersection {
+                point: b.point_at(i.t2),
+                t1,
+                ..i
+            })
+        }),
+    }
+}
+
+/// Intersections of an arc and an elliptical arc, where t1 is the angle on the arc and t2 is the parametric angle in degrees
+///
+/// the arc is approximated by cubic curves much closer than the tolerance, which intersect the elliptical arc
+pub fn arc_elliptical_arc(a: &Arc, e: &EllipticalArc) -> Intersections {
+    let is_on_circle = |p: Point| (p.distance(a.origin) - a.r).abs() <= TOLERANCE;
+    if samples_of_ellipse(e).into_iter().all(is_on_circle) {
+        return on_same_curve(&Shape::Arc(*a), &Shape::EllipticalArc(*e));
+    }
+    pieces_with(
+        &a.to_beziers(TOLERANCE / 10.0),
+        |p| a.project(p).t,
+        |piece| elliptical_arc_bezier(e, piece).swap(),
+    )
+}
+
+/// Intersections of two elliptical arcs, where t1 and t2 are parametric angles in degrees
+///
+/// the second arc is approximated by cubic curves much closer than the tolerance, which intersect the first arc
+pub fn elliptical_arc_elliptical_arc(e1: &EllipticalArc, e2: &EllipticalArc) -> Intersections {
+    let ellipse2 = EllipticalArc::ellipse(e2.origin, e2.rx, e2.ry, e2.rotation);
+    if samples_of_ellipse(e1)
+        .into_iter()
+        .all(|p| ellipse2.project(p).distance <= TOLERANCE)
+    {
+        return on_same_curve(&Shape::EllipticalArc(*e1), &Shape::EllipticalArc(*e2));
+    }
+    pieces_with(
+        &e2.to_beziers(TOLERANCE / 10.0),
+        |p| e2.project(p).t,
+        |piece| elliptical_arc_bezier(e1, piece).swap(),
+    )
+    .swap()
+}
+
+/// Intersections of an arc with any shape, where t1 is on the arc
+fn arc_with(a: &Arc, s: &Shape) -> Intersections {
+    let extent = Extent::Segment;
+    match s {
+        Shape::Point(p) => point_shape(*p, &Shape::Arc(*a)).swap(),
+        Shape::Line(l) => line_arc(l, a, extent).swap(),
+        Shape::Circle(c) => circle_arc(c, a).swap(),
+        Shape::Bezier(b) => arc_bezier(a, b),
+        Shape::Spline(s) => spline_with(s, |b| arc_bezier(a, b).swap()).swap(),
+        Shape::Arc(a2) => arc_arc(a, a2),
+        Shape::EllipticalArc(e) => arc_elliptical_arc(a, e),
+    }
+}
+
+/// Intersections of an elliptical arc with any shape, where t1 is on the elliptical arc
+fn elliptical_arc_with(e: &EllipticalArc, s: &Shape) -> Intersections {
+    let extent = Extent::Segment;
+    match s {
+        Shape::Point(p) => point_shape(*p, &Shape::EllipticalArc(*e)).swap(),
+        Shape::Line(l) => line_elliptical_arc(l, e, extent).swap(),
+        Shape::Circle(c) => arc_elliptical_arc(&Arc::new(c.origin, c.r, 0.0, 360.0), e).swap(),
+        Shape::Bezier(b) => elliptical_arc_bezier(e, b),
+        Shape::Spline(s) => spline_with(s, |b| elliptical_arc_bezier(e, b).swap()).swap(),
+        Shape::Arc(a) => arc_elliptical_arc(a, e).swap(),
+        Shape::EllipticalArc(e2) => elliptical_arc_elliptical_arc(e, e2),
+    }
+}
+
+/// Intersections of cubic pieces approximating a shape with another shape, where t1 is on the approximated shape
+///
+/// - t_at - parameter of the point on the approximated shape
+/// - intersect_piece - intersections where t1 is on the piece
+fn pieces_with<T, F>(pieces: &[Bezier], t_at: T, intersect_piece: F) -> Intersections
+where
+    T: Fn(Point) -> f64,
+    F: Fn(&Bezier) -> Intersections,
+{
+    let mut intersections: Vec<Intersection> = Vec::new();
+    for piece in pieces {
+        match intersect_piece(piece) {
+            Intersections::Coincident | Intersections::Overlapping { .. } => {
+                return Intersections::Coincident
+            }
+            Intersections::Points(points) => {
+                for each in points {
+                    // an intersection at the joint of pieces is found on both pieces
+                    if intersections
+                        .iter()
+                        .all(|i| i.point.distance(each.point) > TOLERANCE)
+                    {
+                        intersections.push(Intersection {
+                            t1: t_at(each.point),
+                            ..each
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Intersections::Points(sorted(intersections))
+}
+
+/// Intersections of shapes on the same circle or ellipse
+///
+/// they are coincident if either one passes through the middle or the inside of the other,
+/// or they meet at tangent points where only their edges touch
+fn on_same_curve(s1: &Shape, s2: &Shape) -> Intersections {
+    let (points1, points2) = (edges_and_middle(s1), edges_and_middle(s2));
+    let on = |s: &Shape, p: Point| s.project(p).distance <= TOLERANCE;
+    let inside = |s: &Shape, points: &[Point; 3], p: &Point| {
+        on(s, *p) && points[0].distance(*p) > TOLERANCE && points[2].distance(*p) > TOLERANCE
+    };
+    if on(s2, points1[1])
+        || on(s1, points2[1])
+        || [points1[0], points1[2]]
+            .iter()
+            .any(|p| inside(s2, &points2, p))
+        || [points2[0], points2[2]]
+            .iter()
+            .any(|p| inside(s1, &points1, p))
+    {
+        return Intersections::Coincident;
+    }
+    let mut points: Vec<Intersection> = Vec::new();
+    for p in [points1[0], points1[2]].iter() {
+        if on(s2, *p) && points.iter().all(|i| i.point.distance(*p) > TOLERANCE) {
+            points.push(Intersection {
+                point: *p,
+                t1: s1.project(*p).t,
+                t2: s2.project(*p).t,
+                kind: IntersectionKind::Tangent,
+            });
+        }
+    }
+    Intersections::Points(sorted(points))
+}
+
+/// Intersections of each segment of a spline with another shape, where t1 is on the spline
+///
+/// an intersection at the joint of segments is found on both segments, which is counted once
+fn spline_with<F>(s: &Spline, intersect_segment: F) -> Intersections
+where
+    F: Fn(&Bezier) -> Intersections,
+{
+    let count_segments = s.segments().len() as f64;
+    let mut intersections: Vec<Intersection> = Vec::new();
+    for (i, b) in s.segments().iter().enumerate() {
+        match intersect_segment(b) {
+            Intersections::Coincident => return Intersections::Coincident,
+            Intersections::Overlapping { t1, t2 } => {
+                let on_spline = |t: f64| (i as f64 + t) / count_segments;
+                return overlapping(
+                    RangeF64 {
+                        from: on_spline(t1.from),
+                        to: on_spline(t1.to),
+                    },
+                    t2,
+                );
+            }
+            Intersections::Points(points) => {
+                for each in points {
+                    let each = Intersection {
+                        t1: (i as f64 + each.t1) / count_segments,
+                        ..each
+                    };
+                    // not only the previous one, since the last segment of a closed spline ends at the first one
+                    if intersections
+                        .iter()
+                        .all(|i| i.point.distance(each.point) > TOLERANCE)
+                    {
+                        intersections.push(each);
+                    }
+                }
+            }
+        }
+    }
+    Intersections::Points(sorted(intersections))
+}
+
+/// Roots of p in the range of the curve, and extrema of p which touch 0 within the tolerance
+///
+/// a curve tangent to another shape makes a double root, which can be slightly above or below 0 by rounding errors
+fn roots_or_touches<F>(b: &Bezier, p: &Polynomial, touches: F) -> Vec<f64>
+where
+    F: Fn(&f64) -> bool,
+{
+    let mut t = b.roots_in_range(p);
+    for extremum in b.roots_in_range(&p.derivative()) {
+        if touches(&extremum)
+            && t.iter()
+                .all(|root| (root - extremum).abs() > TOLERANCE / b.len())
+        {
+            t.push(extremum);
+        }
+    }
+    t
+}
+
+/// A point meets a shape if the shape passes through it, where the parameter on the point is 0
+fn point_shape(p: Point, s: &Shape) -> Intersections {
+    let projection = s.project(p);
+    Intersections::Points(if projection.distance <= TOLERANCE {
+        vec![Intersection {
+            point: p,
+            t1: 0.0,
+            t2: projection.t,
+            kind: IntersectionKind::Crossing,
+        }]
+    } else {
+        Vec::new()
+    })
+}
+
+/// Halve both curves while their control points overlap
+///
+/// - range1, range2 - parameters of the original curves at the edges of the pieces
+fn subdivide(
+    b1: &Bezier,
+    range1: (f64, f64),
+    b2: &Bezier,
+    range2: (f64, f64),
+    depth: usize,
+    candidates: &mut Vec<(f64, f64)>,
+) {
+    let (box1, box2) = (bounding_box(b1), bounding_box(b2));
+    if !overlaps(box1, box2) {
+        return;
+    }
+    let size = |b: (Point, Point)| (b.1 - b.0).norm();
+    let middle1 = (range1.0 + range1.1) / 2.0;
+    let middle2 = (range2.0 + range2.1) / 2.0;
+    if depth >= MAX_DEPTH || (size(box1) <= TOLERANCE && size(box2) <= TOLERANCE) {
+        candidates.push((middle1, middle2));
+        return;
+    }
+    let (split1, split2) = (b1.split_at_t(0.5), b2.split_at_t(0.5));
+    for (piece1, r1) in [
+        (&split1.fst, (range1.0, middle1)),
+        (&split1.snd, (middle1, range1.1)),
+    ]
+    .iter()
+    {
+        for (piece2, r2) in [
+            (&split2.fst, (range2.0, middle2)),
+            (&split2.snd, (middle2, range2.1)),
+        ]
+        .iter()
+        {
+            subdivide(piece1, *r1, piece2, *r2, depth + 1, candidates);
+        }
+    }
+}
+
+/// Refine an intersection by newton's method for B1(t1) = B2(t2), or keep it if newton's method fails
+///
+/// - bounds1, bounds2 - lower and upper bounds of t1 and t2
+fn polish(
+    b1: &Bezier,
+    b2: &Bezier,
+    (t1, t2): (f64, f64),
+    bounds1: (f64, f64),
+    bounds2: (f64, f64),
+) -> (f64, f64) {
+    let (mut s, mut t) = (t1, t2);
+    for _ in 0..10 {
+        let f = b1.point_at(s) - b2.point_at(t);
+        if f.norm() <= TOLERANCE * 1e-3 {
+            break;
+        }
+        let (d1, d2) = (b1.derivative(s), b2.derivative(t));
+        // solve d1 ds - d2 dt = -f
+        let determinant = -cross(d1, d2);
+        if determinant.abs() <= f64::EPSILON * d1.norm() * d2.norm() {
+            break;
+        }
+        s -= cross(f, -1.0 * d2) / determinant;
+        t -= cross(d1, f) / determinant;
+    }
+    let within = |t: f64, (from, to): (f64, f64)| from <= t && t <= to;
+    let gap = |s: f64, t: f64| b1.point_at(s).distance(b2.point_at(t));
+    if within(s, bounds1) && within(t, bounds2) && gap(s, t) < gap(t1, t2) {
+        (s, t)
+    } else {
+        (t1, t2)
+    }
+}
+
+/// true if points of b1 in its range are all on b2
+fn lies_on(b1: &Bezier, b2: &Bezier) -> bool {
+    let range = b1.t_range();
+    (0..=COINCIDENCE_SAMPLES).all(|i| {
+        let t = range.from + (range.to - range.from) * i as f64 / COINCIDENCE_SAMPLES as f64;
+        b2.project(b1.point_at(t)).distance <= TOLERANCE
+    })
+}
+
+/// Common part of curves in their ranges, which is between the edges of either curve lying on the other
+fn common_part(b1: &Bezier, b2: &Bezier) -> Option<Intersections> {
+    let (range1, range2) = (b1.t_range(), b2.t_range());
+    let mut edges: Vec<(f64, f64)> = Vec::new();
+    for t1 in [range1.from, range1.to].iter() {
+        let projection = b2.project(b1.point_at(*t1));
+        if projection.distance <= TOLERANCE {
+            edges.push((*t1, projection.t));
+        }
+    }
+    for t2 in [range2.from, range2.to].iter() {
+        let projection = b1.project(b2.point_at(*t2));
+        if projection.distance <= TOLERANCE {
+            edges.push((projection.t, *t2));
+        }
+    }
+    // curves joined at their edges have the joint twice
+    edges.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    edges.dedup_by(|a, b| b1.point_at(a.0).distance(b1.point_at(b.0)) <= TOLERANCE);
+    if edges.len() != 2 {
+        return None;
+    }
+    let (from, to) = (edges[0], edges[1]);
+    let is_common = (1..COINCIDENCE_SAMPLES).all(|i| {
+        let t = from.0 + (to.0 - from.0) * i as f64 / COINCIDENCE_SAMPLES as f64;
+        b2.project(b1.point_at(t)).distance <= TOLERANCE
+    });
+    if is_common {
+        Some(Intersections::Overlapping {
+            t1: RangeF64 {
+                from: from.0,
+                to: to.0,
+            },
+            t2: RangeF64 {
+                from: from.1,
+                to: to.1,
+            },
+        })
+    } else {
+        None
+    }
+}
+
+/// Intersections of a line and a curve lying on the line
+///
+/// the part of the line which the curve covers is the range of the projection of the curve
+fn line_bezier_on_line(l: &Line, b: &Bezier, x_t: &Polynomial, y_t: &Polynomial) -> Intersections {
+    let a = l.vec();
+    // t on the line of the point of the curve at t
+    let along = ((x_t - Polynomial::new(vec![l.origin.x])) * a.x
+        + (y_t - Polynomial::new(vec![l.origin.y])) * a.y)
+        * (1.0 / (a.norm() * a.norm()));
+    let range = b.t_range();
+    let (from, to) = [range.from, range.to]
+        .iter()
+        .cloned()
+        .chain(b.roots_in_range(&along.derivative()))
+        .map(|t| along.eval(t))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(from, to), t| {
+            (from.min(t), to.max(t))
+        });
+    let (from, to) = (from.max(0.0), to.min(1.0));
+    let overlap = (to - from) * a.norm();
+    if overlap > TOLERANCE {
+        return Intersections::Coincident;
+    }
+    if overlap < -TOLERANCE {
+        return Intersections::Points(Vec::new());
+    }
+    let t1 = from.min(1.0);
+    let point = l.between(t1);
+    Intersections::Points(vec![Intersection {
+        point,
+        t1,
+        t2: b.project(point).t,
+        kind: IntersectionKind::Tangent,
+    }])
+}
+
+/// Overlapping parts, where t1 is turned into ascending order
+fn overlapping(t1: RangeF64, t2: RangeF64) -> Intersections {
+    if t1.from <= t1.to {
+        Intersections::Overlapping { t1, t2 }
+    } else {
+        Intersections::Overlapping {
+            t1: RangeF64 {
+                from: t1.to,
+                to: t1.from,
+            },
+            t2: RangeF64 {
+                from: t2.to,
+                to: t2.from,
+            },
+        }
+    }
+}
+
+/// true if the line has no length
+fn is_degenerate(l: &Line) -> bool {
+    l.len() <= f64::EPSILON
+}
+
+/// true if all the bernstein coefficients of p are within the tolerance, which bound p from 0 to 1
+fn is_zero(p: &Polynomial, tolerance: f64) -> bool {
+    p.to_bernstein().iter().all(|c| c.abs() <= tolerance)
+}
+
+/// Lower left and upper right corners of the control points
+pub(crate) fn bounding_box(b: &Bezier) -> (Point, Point) {
+    b.ctrl_points().iter().fold(
+        (
+            Point::new(f64::INFINITY, f64::INFINITY),
+            Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY),
+        ),
+        |(min, max), p| {
+            (
+                Point::new(min.x.min(p.x), min.y.min(p.y)),
+                Point::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        },
+    )
+}
+
+/// true if bounding boxes overlap within the tolerance
+pub(crate) fn overlaps(box1: (Point, Point), box2: (Point, Point)) -> bool {
+    box1.0.x <= box2.1.x + TOLERANCE
+        && box2.0.x <= box1.1.x + TOLERANCE
+        && box1.0.y <= box2.1.y + TOLERANCE
+        && box2.0.y <= box1.1.y + TOLERANCE
+}
+
+/// t on the line if it is, where t slightly out of the segment is rounded into it
+fn on_line(l: &Line, t: f64, extent: Extent) -> Option<f64> {
+    match extent {
+        Extent::Infinite => Some(t),
+        Extent::Segment => {
+            let margin = TOLERANCE / l.len();
+            if -margin <= t && t <= 1.0 + margin {
+                Some(t.clamp(0.0, 1.0))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Angle on the arc equivalent to the angle if it is, where angles slightly out of the arc are rounded into it
+fn on_arc(a: &Arc, angle: f64) -> Option<f64> {
+    angle_in_sweep(
+        angle,
+        a.start_angle,
+        a.sweep_angle,
+        (TOLERANCE / a.r).to_degrees(),
+    )
+}
+
+/// Parametric angle on the arc equivalent to the angle if it is, where angles slightly out of the arc are rounded into it
+fn on_elliptical_arc(e: &EllipticalArc, angle: f64) -> Option<f64> {
+    angle_in_sweep(
+        angle,
+        e.start_angle,
+        e.sweep_angle,
+        (TOLERANCE / e.rx.max(e.ry)).to_degrees(),
+    )
+}
+
+/// Transformation from an ellipse to the circle, which is the inverse of `EllipticalArc::circle_to_ellipse`
+fn to_circle(circle_to_ellipse: &Transform2) -> Transform2 {
+    circle_to_ellipse
+        .inverse()
+        .expect("Radii of the ellipse must be positive")
+}
+
+/// Points around the whole ellipse of the elliptical arc
+fn samples_of_ellipse(e: &EllipticalArc) -> Vec<Point> {
+    (0..COINCIDENCE_SAMPLES)
+        .map(|i| e.point_at(360.0 * i as f64 / COINCIDENCE_SAMPLES as f64))
+        .collect()
+}
+
+/// The start, the middle, and the end of the shape
+fn edges_and_middle(s: &Shape) -> [Point; 3] {
+    match s {
+        Shape::Bezier(b) => {
+            let range = b.t_range();
+            [
+                b.point_at(range.from),
+                b.point_at((range.from + range.to) / 2.0),
+                b.point_at(range.to),
+            ]
+        }
+        Shape::Point(p) => [*p, *p, *p],
+        Shape::Line(l) => [l.origin, l.between(0.5), l.end],
+        Shape::Circle(c) => [c.point_at(0.0), c.point_at(180.0), c.point_at(0.0)],
+        Shape::Spline(s) => [s.origin(), s.point_at(0.5), s.end()],
+        Shape::Arc(a) => [
+            a.start(),
+            a.point_at(a.start_angle + a.sweep_angle / 2.0),
+            a.end(),
+        ],
+        Shape::EllipticalArc(e) => [
+            e.start(),
+            e.point_at(e.start_angle + e.sweep_angle / 2.0),
+            e.end(),
+        ],
+    }
+}
+
+/// Tangent if directions are parallel, or crossing otherwise
+fn kind(d1: Point, d2: Point) -> IntersectionKind {
+    if cross(d1, d2).abs() <= TANGENT_TOLERANCE * d1.norm() * d2.norm() {
+        IntersectionKind::Tangent
+    } else {
+        IntersectionKind::Crossing
+    }
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.x * b.y - a.y * b.x
+}
+
+fn sorted(mut intersections: Vec<Intersection>) -> Vec<Intersection> {
+    intersections.sort_by(|a, b| a.t1.partial_cmp(&b.t1).unwrap());
+    intersections
+}
+
+#[cfg(test)]
+mod test_intersection {
+    use crate::shapes::{
+        bezier::{Bezier, TOLERANCE},
+        circle::Circle,
+        intersection::{
+            bezier_bezier, circle_bezier, circle_circle, intersect, line_bezier, line_circle,
+            line_line, Extent, Intersection, IntersectionKind, Intersections,
+        },
+        line::Line,
+        point::Point,
+        spline::{Spline, SplineKind},
+        Shape,
+    };
+
+    fn assert_near(p: Point, q: Point, tolerance: f64) {
+        assert!(
+            p.distance(q) <= tolerance,
+            "({}, {}) != ({}, {})",
+            p.x,
+            p.y,
+            q.x,
+            q.y
+        );
+    }
+
+    fn points(intersections: Intersections) -> Vec<Intersection> {
+        match intersections {
+            Intersections::Points(points) => points,
+            _ => panic!("Shapes are not coincident"),
+        }
+    }
+
+    fn line(x0: f64, y0: f64, x1: f64, y1: f64) -> Line {
+        Line::new(Point::new(x0, y0), Point::new(x1, y1))
+    }
+
+    #[test]
+    fn test_line_line() {
+        let crossing = points(line_line(
+            &line(0.0, 0.0, 2.0, 2.0),
+            &line(0.0, 2.0, 2.0, 0.0),
+            Extent::Segment,
+        ));
+        assert_eq!(crossing.len(), 1);
+        assert_near(crossing[0].point, Point::new(1.0, 1.0), 1e-12);
+        assert!((crossing[0].t1 - 0.5).abs() < 1e-12 && (crossing[0].t2 - 0.5).abs() < 1e-12);
+        assert!(crossing[0].kind == IntersectionKind::Crossing);
+        // segments which would cross if they were longer
+        let (l1, l2) = (line(0.0, 0.0, 1.0, 0.0), line(2.0, -1.0, 2.0, 1.0));
+        assert!(points(line_line(&l1, &l2, Extent::Segment)).is_empty());
+        let infinite = points(line_line(&l1, &l2, Extent::Infinite));
+        assert_near(infinite[0].point, Point::new(2.0, 0.0), 1e-12);
+        assert!((infinite[0].t1 - 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_line_line_parallel() {
+        let l = line(0.0, 0.0, 2.0, 0.0);
+        // apart from each other
+        assert!(points(line_line(&l, &line(0.0, 1.0, 2.0, 1.0), Extent::Infinite)).is_empty());
+        // on the same straight line
+        assert!(matches!(
+            line_line(&l, &line(3.0, 0.0, 1.0, 0.0), Extent::Segment),
+            Intersections::Coincident
+        ));
+        assert!(points(line_line(&l, &line(3.0, 0.0, 4.0, 0.0), Extent::Segment)).is_empty());
+        assert!(matches!(
+            line_line(&l, &line(3.0, 0.0, 4.0, 0.0), Extent::Infinite),
+            Intersections::Coincident
+        ));
+        // touching at the edges
+        let touching = points(line_line(&l, &line(2.0, 0.0, 3.0, 0.0), Extent::Segment));
+        assert_eq!(touching.len(), 1);
+        assert_near(touching[0].point, Point::new(2.0, 0.0), 1e-12);
+        assert!((touching[0].t1 - 1.0).abs() < 1e-12 && touching[0].t2.abs() < 1e-12);
+        assert!(touching[0].kind == IntersectionKind::Tangent);
+    }
+
+    #[test]
+    fn test_line_circle() {
+        let c = Circle::new(Point::new(0.0, 0.0), 1.0);
+        let crossing = points(line_circle(&line(-2.0, 0.0, 2.0, 0.0), &c, Extent::Segment));
+        assert_eq!(crossing.len(), 2);
+        assert_near(crossing[0].point, Point::new(-1.0, 0.0), 1e-12);
+        assert_near(crossing[1].point, Point::new(1.0, 0.0), 1e-12);
+        assert!((crossing[0].t2.abs() - 180.0).abs() < 1e-9 && crossing[1].t2.abs() < 1e-9);
+        // touching the top of the circle
+        let tangent = points(line_circle(&line(-2.0, 1.0, 2.0, 1.0), &c, Extent::Segment));
+        assert_eq!(tangent.len(), 1);
+        assert_near(tangent[0].point, Point::new(0.0, 1.0), 1e-12);
+        assert!((tangent[0].t2 - 90.0).abs() < 1e-9);
+        assert!(tangent[0].kind == IntersectionKind::Tangent);
+        assert!(points(line_circle(
+            &line(-2.0, 1.1, 2.0, 1.1),
+            &c,
+            Extent::Infinite
+        ))
+        .is_empty());
+        // the segment ends inside the circle
+        assert_eq!(
+            points(line_circle(&line(0.0, 0.0, 2.0, 0.0), &c, Extent::Segment)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_circle_circle() {
+        let c = Circle::new(Point::new(0.0, 0.0), 1.0);
+        let crossing = points(circle_circle(&c, &Circle::new(Point::new(1.0, 0.0), 1.0)));
+        assert_eq!(crossing.len(), 2);
+        let y = 3.0_f64.sqrt() / 2.0;
+        assert_near(crossing[0].point, Point::new(0.5, -y), 1e-12);
+        assert_near(crossing[1].point, Point::new(0.5, y), 1e-12);
+        let tangent = points(circle_circle(&c, &Circle::new(Point::new(2.0, 0.0), 1.0)));
+        assert_eq!(tangent.len(), 1);
+        assert_near(tangent[0].point, Point::new(1.0, 0.0), 1e-12);
+        assert!(tangent[0].kind == IntersectionKind::Tangent);
+        assert!(matches!(circle_circle(&c, &c), Intersections::Coincident));
+        assert!(points(circle_circle(&c, &Circle::new(Point::new(0.0, 0.0), 2.0))).is_empty());
+    }
+
+    #[test]
+    fn test_bezier_bezier() {
+        let b1 = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, -1.0),
+            Point::new(3.0, 1.0),
+        ]);
+        // straight curve, whose intersections are exact roots of a polynomial as a line
+        let b2 = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.4),
+            Point::new(1.5, 0.4),
+            Point::new(3.0, 0.4),
+        ]);
+        let expected = points(line_bezier(&line(0.0, 0.4, 3.0, 0.4), &b1, Extent::Segment));
+        let subdivided = points(bezier_bezier(&b1, &b2));
+        assert_eq!(expected.len(), 3);
+        assert_eq!(subdivided.len(), 3);
+        for (i, j) in expected.iter().zip(subdivided.iter()) {
+            // roots of the polynomial are within the tolerance
+            assert_near(i.point, j.point, TOLERANCE);
+            assert!((i.t2 - j.t1).abs() < TOLERANCE);
+            // polished to the same point on both curves
+            assert_near(b1.point_at(j.t1), b2.point_at(j.t2), 1e-6);
+            assert!(j.kind == IntersectionKind::Crossing);
+        }
+        assert!(matches!(
+            bezier_bezier(&b1, &b1.clone()),
+            Intersections::Coincident
+        ));
+    }
+
+    #[test]
+    fn test_bezier_bezier_tangent() {
+        // parabola touching a straight curve at its vertex
+        let parabola = Bezier::new_with_ctrl_points(vec![
+            Point::new(-1.0, 1.0),
+            Point::new(0.0, -1.0),
+            Point::new(1.0, 1.0),
+        ]);
+        let straight = Bezier::new_with_ctrl_points(vec![
+            Point::new(-1.0, 0.0),
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+        ]);
+        let tangent = points(bezier_bezier(&parabola, &straight));
+        assert_eq!(tangent.len(), 1);
+        assert_near(tangent[0].point, Point::new(0.0, 0.0), 1e-3);
+        assert!(tangent[0].kind == IntersectionKind::Tangent);
+    }
+
+    #[test]
+    fn test_spline_joint() {
+        // closed spline, whose last segment ends at the start of the first one
+        let s = Spline::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(0.0, 2.0),
+                Point::new(0.0, 0.0),
+            ],
+            SplineKind::Natural,
+        );
+        // the diagonal crosses the joints at both of its ends
+        let diagonal = points(intersect(
+            &Shape::Spline(s),
+            &Shape::Line(line(-1.0, -1.0, 3.0, 3.0)),
+        ));
+        assert_eq!(diagonal.len(), 2);
+        assert_near(diagonal[0].point, Point::new(0.0, 0.0), 1e-6);
+        assert_near(diagonal[1].point, Point::new(2.0, 2.0), 1e-6);
+    }
+
+    #[test]
+    fn test_bezier_bezier_overlapping() {
+        let b = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(2.0, -1.0),
+            Point::new(3.0, 1.0),
+        ]);
+        let (b1, b2) = (b.sub_curve(0.0, 0.6), b.sub_curve(0.4, 1.0));
+        // b from 0.4 to 0.6 is on both of them
+        let assert_overlap =
+            |intersections: Intersections, t1: (f64, f64), t2: (f64, f64)| match intersections {
+                Intersections::Overlapping { t1: r1, t2: r2 } => {
+                    assert!((r1.from - t1.0).abs() < TOLERANCE && (r1.to - t1.1).abs() < TOLERANCE);
+                    assert!((r2.from - t2.0).abs() < TOLERANCE && (r2.to - t2.1).abs() < TOLERANCE);
+                }
+                _ => panic!("Curves overlap partially"),
+            };
+        assert_overlap(bezier_bezier(&b1, &b2), (2.0 / 3.0, 1.0), (0.0, 1.0 / 3.0));
+        assert_overlap(bezier_bezier(&b2, &b1), (0.0, 1.0 / 3.0), (2.0 / 3.0, 1.0));
+        // t1 is in ascending order even if the second curve goes back
+        let mut reversed = b2.clone();
+        reversed.reverse();
+        assert_overlap(
+            bezier_bezier(&b1, &reversed),
+            (2.0 / 3.0, 1.0),
+            (1.0, 2.0 / 3.0),
+        );
+        // curves joined at their edges only meet at the joint
+        let joined = points(bezier_bezier(
+            &b.sub_curve(0.0, 0.5),
+            &b.sub_curve(0.5, 1.0),
+        ));
+        assert_eq!(joined.len(), 1);
+        assert_near(joined[0].point, b.point_at(0.5), TOLERANCE);
+    }
+
+    #[test]
+    fn test_line_bezier_on_line() {
+        // straight curve which goes forward to x = 2 and back to x = 0
+        let back_and_forth = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(4.0, 0.0),
+            Point::new(0.0, 0.0),
+        ]);
+        assert!(matches!(
+            line_bezier(&line(1.5, 0.0, 3.0, 0.0), &back_and_forth, Extent::Segment),
+            Intersections::Coincident
+        ));
+        assert!(points(line_bezier(
+            &line(3.0, 0.0, 4.0, 0.0),
+            &back_and_forth,
+            Extent::Segment
+        ))
+        .is_empty());
+        // the middle of the curve is not on the line, which only overlaps its end
+        let straight = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(3.0, 0.0),
+        ]);
+        assert!(matches!(
+            line_bezier(&line(2.0, 0.0, 5.0, 0.0), &straight, Extent::Segment),
+            Intersections::Coincident
+        ));
+        let touching = points(line_bezier(
+            &line(3.0, 0.0, 5.0, 0.0),
+            &straight,
+            Extent::Segment,
+        ));
+        assert_eq!(touching.len(), 1);
+        assert_near(touching[0].point, Point::new(3.0, 0.0), 1e-12);
+        assert!(touching[0].t1.abs() < 1e-12 && (touching[0].t2 - 1.0).abs() < 1e-6);
+        assert!(touching[0].kind == IntersectionKind::Tangent);
+        // a curve slightly off the line within the tolerance
+        let almost_straight = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1e-4),
+            Point::new(2.0, 0.0),
+        ]);
+        assert!(matches!(
+            line_bezier(
+                &line(0.0, 0.0, 2.0, 0.0),
+                &almost_straight,
+                Extent::Infinite
+            ),
+            Intersections::Coincident
+        ));
+        // a line without its length meets nothing
+        assert!(points(line_bezier(
+            &line(1.0, 0.0, 1.0, 0.0),
+            &straight,
+            Extent::Segment
+        ))
+        .is_empty());
+    }
+
+    #[test]
+    fn test_circle_bezier_on_circle() {
+        let c = Circle::new(Point::new(0.0, 0.0), 1.0);
+        // arc of 30 degrees approximated much closer than the tolerance
+        let (angle, k) = (30_f64.to_radians(), 4.0 / 3.0 * 7.5_f64.to_radians().tan());
+        let end = Point::new(angle.cos(), angle.sin());
+        let arc = Bezier::new_with_ctrl_points(vec![
+            Point::new(1.0, 0.0),
+            Point::new(1.0, k),
+            end + k * Point::new(angle.sin(), -angle.cos()),
+            end,
+        ]);
+        assert!(matches!(circle_bezier(&c, &arc), Intersections::Coincident));
+        // the same arc on a slightly larger circle
+        let larger = Circle::new(Point::new(0.0, 0.0), 1.01);
+        assert!(points(circle_bezier(&larger, &arc)).is_empty());
+    }
+
+    #[test]
+    fn test_line_circle_degenerate() {
+        let c = Circle::new(Point::new(0.0, 0.0), 1.0);
+        assert!(points(line_circle(&line(0.5, 0.0, 0.5, 0.0), &c, Extent::Segment)).is_empty());
+        assert!(points(line_circle(&line(0.5, 0.0, 0.5, 0.0), &c, Extent::Infinite)).is_empty());
+    }
+}
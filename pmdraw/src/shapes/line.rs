@@ -1,5 +1,11 @@
-use super::{bezier::Bezier, point::Point};
-use pmmath::matrix::Mat;
+use super::{
+    bezier::Bezier,
+    intersection::{line_line, Extent, Intersections},
+    lengths_every,
+    point::Point,
+    projection::Projection,
+};
+use pmmath::transform::Transform2;
 use std::{f64::consts::PI, ops};
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -45,66 +51,49 @@ impl Line {
         self.origin = self.origin - (length / self.len()) * self.vec()
     }
 
-    /// Get point where two lines intersect
-    ///
-    /// f(t) = t1 * a1 - t2 * a2 = c
-    ///     where
-    ///         a1 = end1 - origin1
-    ///         a2 = end2 - origin2
-    ///         b = -origin1 + origin2
-    pub fn intersection(&self, l: &Line) -> Point {
-        // vertical
-        if self.vec().x == 0.0 {
-            return l.at_x(self.origin.x);
-        }
-        // horizontal
-        if self.vec().y == 0.0 {
-            return l.at_y(self.origin.y);
+    /// Move the edge of the line nearer to p onto p
+    pub fn extend_to(&mut self, p: Point) {
+        if self.origin.distance(p) < self.end.distance(p) {
+            self.origin = p;
+        } else {
+            self.end = p;
         }
+    }
 
-        let mut a = Mat::new(2, 2);
-        let a1 = self.end - self.origin;
-        let a2 = l.end - l.origin;
-        a[0][0] = a1.x;
-        a[0][1] = -a2.x;
-        a[1][0] = a1.y;
-        a[1][1] = -a2.y;
-        let mut b = Mat::new(2, 1);
-        let c = l.origin - self.origin;
-        b[0][0] = c.x;
-        b[1][0] = c.y;
-        let solve = a.inverse().unwrap() * b;
-        // use smaller t to reduce error
-        let t1 = solve[0][0];
-        let t2 = solve[1][0];
-        if t1 > t2 {
-            l.between(t2)
-        } else {
-            self.between(t1)
+    /// Get point where two infinite lines intersect, or none if they are parallel
+    ///
+    /// use `intersection::line_line` for segments or all the details of the intersection
+    pub fn intersection(&self, l: &Line) -> Option<Point> {
+        match line_line(self, l, Extent::Infinite) {
+            Intersections::Points(points) => points.first().map(|i| i.point),
+            _ => None,
         }
     }
 
     /// Join two lines by extending both of them
+    ///
+    /// parallel lines are kept as they are, since they never meet
     pub fn join(&mut self, l: &mut Line) -> () {
-        let intersection = self.intersection(l);
-        let extend = |l: &mut Line| {
-            let d_origin = l.origin.distance(intersection);
-            let d_end = l.end.distance(intersection);
-            if d_origin < d_end {
-                l.origin = intersection;
-            } else {
-                l.end = intersection;
-            }
-        };
-        extend(self);
-        extend(l);
+        if let Some(intersection) = self.intersection(l) {
+            self.extend_to(intersection);
+            l.extend_to(intersection);
+        }
     }
 
+    /// Join with a curve by extending the line and the origin of the curve backward along its tangent,
+    /// and get the extension from the origin of the curve
+    ///
+    /// the line is kept and bridged from the origin if they are parallel
     pub fn join_bezier(&mut self, b: &mut Bezier) -> Line {
-        let p = b.point_at(b.t_range().from + 0.01);
-        let mut bezier_edge = b.range.from.line_to(p);
-        self.join(&mut bezier_edge);
-        bezier_edge
+        let origin = b.range().from;
+        let bezier_edge = (origin - b.tangent(b.t_range().from)).line_to(origin);
+        match self.intersection(&bezier_edge) {
+            Some(intersection) => {
+                self.extend_to(intersection);
+                origin.line_to(intersection)
+            }
+            None => origin.line_to(self.end),
+        }
     }
 
     pub fn midddle(&self) -> Point {
@@ -143,6 +132,11 @@ impl Line {
         }
     }
 
+    /// Point at the length along the line from the origin, same as `point_from_origin`
+    pub fn point_at_length(&self, length: f64) -> Point {
+        self.point_from_origin(length)
+    }
+
     /// Get a point on this line by specifying distance from the line's end
     pub fn point_from_end(&self, length: f64) -> Point {
         self.end.to_point(self.origin, length)
@@ -153,6 +147,32 @@ impl Line {
         self.origin.to_point(self.end, length)
     }
 
+    /// Points at even intervals of length from the origin
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(self.len(), interval)
+            .into_iter()
+            .map(|length| self.point_at_length(length))
+            .collect()
+    }
+
+    /// Nearest point on the line segment to p, where t is what `between` takes
+    pub fn project(&self, p: Point) -> Projection {
+        let t = self.project_infinite(p).t.clamp(0.0, 1.0);
+        Projection::new(t, self.between(t), p)
+    }
+
+    /// Nearest point on the infinite line to p, i.e. the foot of the perpendicular, where t is what `between` takes
+    pub fn project_infinite(&self, p: Point) -> Projection {
+        let v = self.vec();
+        let len_squared = v.x * v.x + v.y * v.y;
+        let t = if len_squared > 0.0 {
+            ((p.x - self.origin.x) * v.x + (p.y - self.origin.y) * v.y) / len_squared
+        } else {
+            0.0
+        };
+        Projection::new(t, self.between(t), p)
+    }
+
     pub fn reverse(&mut self) -> () {
         let origin = self.origin.clone();
         self.origin = self.end.clone();
@@ -165,6 +185,15 @@ impl Line {
         self.end.rotate(angle_degree, point);
     }
 
+    /// Split the line at the length from the origin and get two separate lines
+    pub fn split_at_length(&self, length: f64) -> Split {
+        let split_point = self.point_at_length(length);
+        Split {
+            fst: Line::new(self.origin, split_point),
+            snd: Line::new(split_point, self.end),
+        }
+    }
+
     /// Split the line at a point and get two separate lines
     pub fn split_at_x(&self, x: f64) -> Split {
         let split_point = self.at_x(x);
@@ -174,12 +203,23 @@ impl Line {
         }
     }
 
+    /// Unit tangent at the length from the origin, which is the direction of the line anywhere
+    pub fn tangent_at_length(&self, _length: f64) -> Point {
+        self.vec().normalize()
+    }
+
     /// move line
     pub fn to(&mut self, dx: f64, dy: f64) {
         self.origin = self.origin.to(dx, dy);
         self.end = self.end.to(dx, dy);
     }
 
+    /// Transform both edges by an affine transformation
+    pub fn transform(&mut self, m: &Transform2) {
+        self.origin = self.origin.transform(m);
+        self.end = self.end.transform(m);
+    }
+
     pub fn vec(&self) -> Point {
         self.end - self.origin
     }
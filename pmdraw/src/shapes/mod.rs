@@ -1,9 +1,21 @@
-use self::{bezier::Bezier, circle::Circle, line::Line, point::Point};
+use pmmath::transform::Transform2;
 
+use self::{
+    arc::Arc, bezier::Bezier, circle::Circle, ellipse::EllipticalArc, geometry::Geometry,
+    intersection::Intersections, line::Line, point::Point, spline::Spline,
+};
+
+pub mod arc;
 pub mod bezier;
 pub mod circle;
+pub mod ellipse;
+pub mod geometry;
+pub mod intersection;
 pub mod line;
+pub mod offset;
 pub mod point;
+pub mod projection;
+pub mod spline;
 
 #[derive(Clone)]
 pub enum Shape {
@@ -11,4 +23,147 @@ pub enum Shape {
     Point(Point),
     Line(Line),
     Circle(Circle),
+    Spline(Spline),
+    Arc(Arc),
+    EllipticalArc(EllipticalArc),
+}
+
+impl Shape {
+    /// The shape as what every shape can do
+    pub fn geometry(&self) -> &dyn Geometry {
+        match self {
+            Shape::Bezier(b) => b,
+            Shape::Point(p) => p,
+            Shape::Line(l) => l,
+            Shape::Circle(c) => c,
+            Shape::Spline(s) => s,
+            Shape::Arc(a) => a,
+            Shape::EllipticalArc(e) => e,
+        }
+    }
+
+    /// All points where the shape meets another, where lines are segments
+    pub fn intersect(&self, another: &Shape) -> Intersections {
+        intersection::intersect(self, another)
+    }
+
+    /// Swap the start and the end, where a circle becomes the arc going around clockwise
+    pub fn reverse(&mut self) {
+        *self = self.reversed()
+    }
+
+    /// Transform by an affine transformation, where circles and arcs become elliptical arcs if it does not keep them round
+    pub fn transform(&mut self, m: &Transform2) {
+        *self = self.transformed(m)
+    }
+}
+
+/// Lengths from 0 at even intervals which do not exceed the total length
+///
+/// the total length itself is included if it is a multiple of the interval within rounding errors
+pub(crate) fn lengths_every(total: f64, interval: f64) -> Vec<f64> {
+    assert!(interval > 0.0, "Interval must be positive");
+    let count = (total / interval * (1.0 + 1e-12)).floor() as usize;
+    (0..=count).map(|i| i as f64 * interval).collect()
+}
+
+#[cfg(test)]
+mod test_length {
+    use std::f64::consts::PI;
+
+    use crate::shapes::{
+        bezier::{Bezier, TOLERANCE},
+        circle::Circle,
+        lengths_every,
+        line::Line,
+        point::Point,
+        spline::{Spline, SplineKind},
+    };
+
+    fn arch() -> Bezier {
+        Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 3.0),
+            Point::new(4.0, 3.0),
+            Point::new(5.0, 0.0),
+        ])
+    }
+
+    #[test]
+    fn test_lengths_every() {
+        assert_eq!(lengths_every(1.0, 0.25), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        assert_eq!(lengths_every(0.9, 0.5), vec![0.0, 0.5]);
+        // 0.3 is not exactly 3 times 0.1
+        assert_eq!(lengths_every(0.3, 0.1).len(), 4);
+    }
+
+    #[test]
+    fn test_line() {
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(3.0, 4.0));
+        assert!(l.point_at_length(2.5).distance(Point::new(1.5, 2.0)) < 1e-12);
+        let split = l.split_at_length(2.5);
+        assert!(split.fst.end == split.snd.origin);
+        assert!((split.fst.len() - 2.5).abs() < 1e-12 && (split.snd.len() - 2.5).abs() < 1e-12);
+        let points = l.points_every_length(2.0);
+        assert_eq!(points.len(), 3);
+        for pair in points.windows(2) {
+            assert!((pair[0].distance(pair[1]) - 2.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_circle() {
+        let c = Circle::new(Point::new(1.0, 1.0), 2.0);
+        // a quarter of the circumference from the angle 0
+        assert!(c.point_at_length(PI).distance(Point::new(1.0, 3.0)) < 1e-12);
+        let points = c.points_every_length(PI);
+        assert_eq!(points.len(), 5);
+        assert!(points[0].distance(points[4]) < 1e-12);
+    }
+
+    #[test]
+    fn test_bezier() {
+        let b = arch();
+        let len = b.len();
+        // the arch is symmetric
+        assert!(b.point_at_length(len / 2.0).distance(b.point_at(0.5)) < TOLERANCE);
+        let split = b.split_at_length(2.0);
+        assert!((split.fst.len() - 2.0).abs() < TOLERANCE);
+        assert!((split.snd.len() - (len - 2.0)).abs() < TOLERANCE);
+        let points = b.points_every_length(1.0);
+        assert_eq!(points.len(), len.floor() as usize + 1);
+        for (i, p) in points.iter().enumerate() {
+            let t = b.project(*p).t;
+            assert!((b.sub_curve(0.0, t).len() - i as f64).abs() < TOLERANCE);
+        }
+        // t of the length of the curve up to t is t itself
+        for i in 1..10 {
+            let t = i as f64 / 10.0;
+            assert!((b.t_at_length(b.sub_curve(0.0, t).len()) - t).abs() < TOLERANCE);
+        }
+    }
+
+    #[test]
+    fn test_spline() {
+        let fit_points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 2.0),
+            Point::new(4.0, 0.0),
+            Point::new(6.0, 2.0),
+        ];
+        let spline = Spline::new(fit_points.clone(), SplineKind::Centripetal);
+        let segments = spline.segments();
+        // lengths go through the segments
+        assert!((spline.len() - segments.iter().map(|b| b.len()).sum::<f64>()).abs() < 1e-12);
+        let p = spline.point_at_length(segments[0].len() + segments[1].len());
+        assert!(p.distance(fit_points[2]) < TOLERANCE);
+        let split = spline.split_at_length(3.0);
+        assert!((split.fst.len() - 3.0).abs() < TOLERANCE);
+        assert!((split.snd.len() - (spline.len() - 3.0)).abs() < TOLERANCE);
+        let points = spline.points_every_length(1.0);
+        assert_eq!(points.len(), spline.len().floor() as usize + 1);
+        assert!(points[1].distance(spline.point_at_length(1.0)) < 1e-12);
+        // the whole length, which is the sum of lengths of segments, reaches the end
+        assert!(spline.point_at_length(spline.len()).distance(spline.end()) < TOLERANCE);
+    }
 }
@@ -0,0 +1,285 @@
+use super::{
+    bezier::{Bezier, TOLERANCE},
+    intersection::{bezier_bezier, bounding_box, overlaps, Intersections},
+    point::Point,
+};
+
+/// number of intervals of a piece where the offset is sampled
+const SAMPLES: usize = 8;
+/// give up halving pieces after this number of halvings
+const MAX_DEPTH: usize = 12;
+
+/// Cubic curves at the distance from the chain of curves, within the tolerance
+///
+/// positive distance is on the right side of the direction of the curves, and negative is on the left.
+/// loops made where curves turn tighter than the distance are trimmed
+pub(crate) fn offset(curves: &[Bezier], distance: f64, tolerance: f64) -> Vec<Bezier> {
+    let mut pieces = Vec::new();
+    for b in curves {
+        let range = b.t_range();
+        let b = b.sub_curve(range.from, range.to);
+        let curve = Curve { b: &b, distance };
+        curve.approximate(0.0, 1.0, tolerance, 0, &mut pieces);
+    }
+    trim_loops(pieces)
+}
+
+/// Curve to offset and the distance
+struct Curve<'a> {
+    b: &'a Bezier,
+    distance: f64,
+}
+
+impl Curve<'_> {
+    /// Approximate the offset from t0 to t1 by a cubic curve, or by halves of it if it is not within the tolerance
+    ///
+    /// the cubic starts and ends at the exact offset in the same directions as the curve,
+    /// and lengths of its control arms are fit to samples of the offset in the least squares sense
+    fn approximate(
+        &self,
+        t0: f64,
+        t1: f64,
+        tolerance: f64,
+        depth: usize,
+        pieces: &mut Vec<Bezier>,
+    ) {
+        let t_at = |k: f64| t0 + (t1 - t0) * k / SAMPLES as f64;
+        let samples = (0..=SAMPLES)
+            .map(|k| self.point_at(t_at(k as f64)))
+            .collect::<Vec<Point>>();
+        let (origin, end) = (samples[0], samples[SAMPLES]);
+        let (d0, d1) = (self.b.tangent(t0), self.b.tangent(t1));
+        let (a, b) = self.fit_arms(&samples, d0, d1).unwrap_or_else(|| {
+            let arm = origin.distance(end) / 3.0;
+            (arm, arm)
+        });
+        let piece = Bezier::new_with_ctrl_points(vec![origin, origin + a * d0, end - b * d1, end]);
+        let error = (0..SAMPLES)
+            .map(|k| piece.project(self.point_at(t_at(k as f64 + 0.5))).distance)
+            .chain(
+                samples[1..SAMPLES]
+                    .iter()
+                    .map(|p| piece.project(*p).distance),
+            )
+            .fold(0.0, f64::max);
+        // negative arms mean the offset reverses its direction, i.e. it makes a cusp
+        let is_reversed = a < 0.0 || b < 0.0;
+        if depth >= MAX_DEPTH || (error <= tolerance && !is_reversed) {
+            pieces.push(piece);
+        } else {
+            let middle = (t0 + t1) / 2.0;
+            self.approximate(t0, middle, tolerance, depth + 1, pieces);
+            self.approximate(middle, t1, tolerance, depth + 1, pieces);
+        }
+    }
+
+    /// Lengths of arms from the origin and the end which fit the cubic to samples best
+    ///
+    /// none if they are not solved, e.g. the samples are on a straight line parallel to the arms
+    fn fit_arms(&self, samples: &[Point], d0: Point, d1: Point) -> Option<(f64, f64)> {
+        let n = samples.len() - 1;
+        let (origin, end) = (samples[0], samples[n]);
+        // u in proportion to the distance along the samples
+        let mut u = vec![0.0];
+        for k in 1..=n {
+            u.push(u[k - 1] + samples[k - 1].distance(samples[k]));
+        }
+        let total = u[n];
+        if total <= 0.0 {
+            return None;
+        }
+        // C(u) = base(u) + a b1(u) d0 - b b2(u) d1, whose error from each sample is minimized
+        let (mut a11, mut a12, mut a22, mut c1, mut c2) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        let dot = |p: Point, q: Point| p.x * q.x + p.y * q.y;
+        for k in 1..n {
+            let u = u[k] / total;
+            let (b0, b1, b2, b3) = (
+                (1.0 - u).powi(3),
+                3.0 * (1.0 - u).powi(2) * u,
+                3.0 * (1.0 - u) * u * u,
+                u.powi(3),
+            );
+            let r = (b0 + b1) * origin + (b2 + b3) * end - samples[k];
+            let (v1, v2) = (b1 * d0, -b2 * d1);
+            a11 += dot(v1, v1);
+            a12 += dot(v1, v2);
+            a22 += dot(v2, v2);
+            c1 -= dot(v1, r);
+            c2 -= dot(v2, r);
+        }
+        let determinant = a11 * a22 - a12 * a12;
+        if determinant.abs() <= f64::EPSILON * a11 * a22 {
+            return None;
+        }
+        Some((
+            (c1 * a22 - c2 * a12) / determinant,
+            (a11 * c2 - a12 * c1) / determinant,
+        ))
+    }
+
+    /// Exact point at the distance from the curve at t
+    fn point_at(&self, t: f64) -> Point {
+        self.b.point_at(t) - self.distance * self.b.normal(t)
+    }
+}
+
+/// Cut off loops between pieces which cross each other, keeping the outermost crossing
+///
+/// only pairs of pieces whose bounding boxes overlap are intersected, which are a few of all the pairs
+fn trim_loops(mut pieces: Vec<Bezier>) -> Vec<Bezier> {
+    let mut boxes = pieces.iter().map(bounding_box).collect::<Vec<_>>();
+    let mut i = 0;
+    while i < pieces.len() {
+        // the farthest piece crossing piece i, apart from the joint to the next piece
+        let crossing = (i + 1..pieces.len()).rev().find_map(|j| {
+            if !overlaps(boxes[i], boxes[j]) {
+                return None;
+            }
+            let points = match bezier_bezier(&pieces[i], &pieces[j]) {
+                Intersections::Points(points) => points,
+                _ => Vec::new(),
+            };
+            points
+                .into_iter()
+                .filter(|c| j > i + 1 || c.point.distance(pieces[i].end()) > TOLERANCE)
+                .min_by(|a, b| a.t1.partial_cmp(&b.t1).unwrap())
+                .map(|c| (j, c.t1, c.t2))
+        });
+        if let Some((j, t1, t2)) = crossing {
+            let fst = pieces[i].sub_curve(0.0, t1);
+            let snd = pieces[j].sub_curve(t2, 1.0);
+            let keeps_fst = !is_degenerate(&fst);
+            let trimmed = vec![fst, snd]
+                .into_iter()
+                .filter(|b| !is_degenerate(b))
+                .collect::<Vec<Bezier>>();
+            boxes.splice(i..=j, trimmed.iter().map(bounding_box));
+            pieces.splice(i..=j, trimmed);
+            if !keeps_fst {
+                // the piece after the loop is now at i, which may cross another piece too
+                continue;
+            }
+        }
+        i += 1;
+    }
+    pieces
+}
+
+/// true if the piece is as small as a point
+fn is_degenerate(b: &Bezier) -> bool {
+    b.origin().distance(b.end()) <= TOLERANCE && b.len() <= TOLERANCE
+}
+
+#[cfg(test)]
+mod test_offset {
+    use crate::shapes::{
+        bezier::{Bezier, TOLERANCE},
+        intersection::bezier_bezier,
+        offset::offset,
+        point::Point,
+    };
+
+    fn curve(points: Vec<(f64, f64)>) -> Bezier {
+        Bezier::new_with_ctrl_points(points.into_iter().map(|(x, y)| Point::new(x, y)).collect())
+    }
+
+    /// largest difference of the distance from the curves to points of the pieces from the offset distance
+    fn max_error(curves: &[Bezier], pieces: &[Bezier], distance: f64) -> f64 {
+        let mut error: f64 = 0.0;
+        for piece in pieces {
+            for k in 0..=32 {
+                let p = piece.point_at(k as f64 / 32.0);
+                let nearest = curves
+                    .iter()
+                    .map(|b| b.project(p).distance)
+                    .fold(f64::INFINITY, f64::min);
+                error = error.max((nearest - distance.abs()).abs());
+            }
+        }
+        error
+    }
+
+    /// pieces which cross each other, apart from joints of adjacent pieces
+    fn count_crossings(pieces: &[Bezier]) -> usize {
+        let mut count = 0;
+        for i in 0..pieces.len() {
+            for j in i + 1..pieces.len() {
+                count += bezier_bezier(&pieces[i], &pieces[j])
+                    .points()
+                    .iter()
+                    .filter(|c| j > i + 1 || c.point.distance(pieces[i].end()) > TOLERANCE)
+                    .count();
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_offset_distance() {
+        let arch = curve(vec![(0.0, 0.0), (1.0, 3.0), (4.0, 3.0), (5.0, 0.0)]);
+        for distance in [-0.5, 0.5].iter() {
+            let tolerance = 0.01;
+            let pieces = offset(&[arch.clone()], *distance, tolerance);
+            assert!(max_error(&[arch.clone()], &pieces, *distance) <= tolerance);
+            // pieces are chained
+            for pair in pieces.windows(2) {
+                assert!(pair[0].end().distance(pair[1].origin()) < 1e-9);
+            }
+        }
+        // outside of the arch, which is on the left
+        let outer = offset(&[arch.clone()], -0.5, 0.01);
+        assert!(
+            outer[0]
+                .origin()
+                .distance(Point::new(-0.5 * 0.9487, 0.5 * 0.3162))
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn test_offset_trims_loop() {
+        // the vertex turns with the radius of 0.5, which is smaller than the distance inside
+        let tight = curve(vec![(0.0, 0.0), (1.0, 2.0), (2.0, 0.0)]);
+        let pieces = offset(&[tight.clone()], 1.0, TOLERANCE);
+        assert_eq!(count_crossings(&pieces), 0);
+        // every point of the trimmed offset is at least the distance away from the curve
+        for piece in pieces.iter() {
+            for k in 0..=32 {
+                let p = piece.point_at(k as f64 / 32.0);
+                assert!(tight.project(p).distance >= 1.0 - TOLERANCE);
+            }
+        }
+        // the loop is cut off at the crossing on the axis of the curve
+        let (start, end) = (pieces[0].origin(), pieces[pieces.len() - 1].end());
+        assert!(pieces.iter().any(|b| (b.end().x - 1.0).abs() < TOLERANCE));
+        assert!(start.x < 1.0 && end.x > 1.0);
+    }
+
+    #[test]
+    fn test_offset_corner() {
+        // straight curves turning left at (2, 0)
+        let chain = [
+            curve(vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]),
+            curve(vec![(2.0, 0.0), (2.0, 1.0), (2.0, 2.0)]),
+        ];
+        // outside of the corner, pieces of the curves leave a gap between them
+        let outer = offset(&chain, 0.5, TOLERANCE);
+        let gaps = outer
+            .windows(2)
+            .filter(|pair| pair[0].end().distance(pair[1].origin()) > TOLERANCE)
+            .map(|pair| (pair[0].end(), pair[1].origin()))
+            .collect::<Vec<(Point, Point)>>();
+        assert_eq!(gaps.len(), 1);
+        assert!(gaps[0].0.distance(Point::new(2.0, -0.5)) < 1e-9);
+        assert!(gaps[0].1.distance(Point::new(2.5, 0.0)) < 1e-9);
+        // inside of the corner, they cross each other and are trimmed at the crossing
+        let inner = offset(&chain, -0.5, TOLERANCE);
+        assert_eq!(count_crossings(&inner), 0);
+        for pair in inner.windows(2) {
+            assert!(pair[0].end().distance(pair[1].origin()) < TOLERANCE);
+        }
+        assert!(inner
+            .iter()
+            .any(|b| b.end().distance(Point::new(1.5, 0.5)) < TOLERANCE));
+    }
+}
@@ -1,3 +1,4 @@
+use pmmath::transform::Transform2;
 use std::ops;
 
 use std::f64::consts::PI;
@@ -42,7 +43,9 @@ impl Point {
         let l_vec = mirror_line.vec();
         let vertical_direction = Point::new(-l_vec.y, l_vec.x);
         let vertical_line = self.line_to(self + vertical_direction);
-        let vertical_point_on_the_mirror = mirror_line.intersection(&&vertical_line);
+        let vertical_point_on_the_mirror = mirror_line
+            .intersection(&vertical_line)
+            .expect("Mirror line must have its length");
         if self == &vertical_point_on_the_mirror {
             vertical_point_on_the_mirror
         } else {
@@ -133,6 +136,12 @@ impl Point {
             Axis::Z => Point::new3(x + d1, y + d2, z),
         }
     }
+
+    /// Transform x and y by an affine transformation, z is kept as it is
+    pub fn transform(&self, m: &Transform2) -> Point {
+        let (x, y) = m.apply(self.x, self.y);
+        Point::new3(x, y, self.z)
+    }
 }
 
 impl_op_ex!(+|p1: &Point, p2: &Point| -> Point {
@@ -0,0 +1,149 @@
+use super::point::Point;
+
+/// Nearest point on a shape to a given point
+#[derive(Copy, Clone)]
+pub struct Projection {
+    /// parameter of the nearest point, which is what `point_at` or `between` of the shape takes
+    pub t: f64,
+    /// the nearest point on the shape, i.e. the foot of the perpendicular if it is not an edge
+    pub point: Point,
+    /// distance from the given point to the nearest point
+    pub distance: f64,
+}
+
+impl Projection {
+    pub fn new(t: f64, point: Point, p: Point) -> Projection {
+        Projection {
+            t,
+            point,
+            distance: point.distance(p),
+        }
+    }
+
+    /// The nearest one of projections, or none if there are none
+    pub fn nearest<I>(projections: I) -> Option<Projection>
+    where
+        I: IntoIterator<Item = Projection>,
+    {
+        projections
+            .into_iter()
+            .fold(None, |nearest, each| match nearest {
+                Some(nearest) if nearest.distance <= each.distance => Some(nearest),
+                _ => Some(each),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test_projection {
+    use crate::shapes::{
+        bezier::Bezier,
+        circle::Circle,
+        line::Line,
+        point::Point,
+        projection::Projection,
+        spline::{Spline, SplineKind},
+    };
+
+    /// nearest of points at many t from 0 to 1, and the number of local minima of the distance among them
+    fn nearest_sample<F>(point_at: F, p: Point) -> (Projection, usize)
+    where
+        F: Fn(f64) -> Point,
+    {
+        let count_samples = 10000;
+        let projections = (0..=count_samples)
+            .map(|i| {
+                let t = i as f64 / count_samples as f64;
+                Projection::new(t, point_at(t), p)
+            })
+            .collect::<Vec<Projection>>();
+        let count_minima = projections
+            .windows(3)
+            .filter(|w| w[1].distance < w[0].distance && w[1].distance < w[2].distance)
+            .count();
+        (Projection::nearest(projections).unwrap(), count_minima)
+    }
+
+    #[test]
+    fn test_project_line() {
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0));
+        let projection = l.project(Point::new(1.0, 2.0));
+        assert!(projection.t == 0.25 && projection.point == Point::new(1.0, 0.0));
+        assert_eq!(projection.distance, 2.0);
+        // beyond the edges, the edges are the nearest
+        let projection = l.project(Point::new(-3.0, 4.0));
+        assert!(projection.t == 0.0 && projection.distance == 5.0);
+        assert_eq!(l.project(Point::new(6.0, 1.0)).t, 1.0);
+    }
+
+    #[test]
+    fn test_project_infinite() {
+        let l = Line::new(Point::new(0.0, 0.0), Point::new(4.0, 0.0));
+        // beyond the edges, the foot of the perpendicular is out of 0 to 1
+        let projection = l.project_infinite(Point::new(-3.0, 4.0));
+        assert!(projection.t == -0.75 && projection.point == Point::new(-3.0, 0.0));
+        assert_eq!(projection.distance, 4.0);
+        assert_eq!(l.project_infinite(Point::new(6.0, 1.0)).t, 1.5);
+        // a line without its length is its origin
+        let point = Line::new(Point::new(1.0, 1.0), Point::new(1.0, 1.0));
+        assert_eq!(point.project_infinite(Point::new(4.0, 5.0)).distance, 5.0);
+    }
+
+    #[test]
+    fn test_project_circle() {
+        let c = Circle::new(Point::new(1.0, 1.0), 2.0);
+        let projection = c.project(Point::new(1.0, 4.0));
+        assert!((projection.t - 90.0).abs() < 1e-9);
+        assert!(projection.point.distance(Point::new(1.0, 3.0)) < 1e-9);
+        assert!((projection.distance - 1.0).abs() < 1e-9);
+        // inside the circle
+        let projection = c.project(Point::new(0.0, 1.0));
+        assert!((projection.t - 180.0).abs() < 1e-9 && (projection.distance - 1.0).abs() < 1e-9);
+        // every point is equally near from the center
+        let projection = c.project(c.origin);
+        assert!(projection.t == 0.0 && (projection.distance - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_project_bezier() {
+        // inside the u shape, the distance is locally minimum on both arms
+        let b = Bezier::new_with_ctrl_points(vec![
+            Point::new(0.0, 4.0),
+            Point::new(3.0, -4.0),
+            Point::new(6.0, 4.0),
+        ]);
+        let p = Point::new(3.5, 3.0);
+        let (nearest, count_minima) = nearest_sample(|t| b.point_at(t), p);
+        assert!(count_minima >= 2);
+        let projection = b.project(p);
+        assert!(projection.t > 0.5 && (projection.t - nearest.t).abs() < 1e-3);
+        assert!(projection.distance <= nearest.distance + 1e-9);
+        // the edges are the nearest beyond them
+        assert_eq!(b.project(Point::new(-1.0, 5.0)).t, 0.0);
+        assert_eq!(b.project(Point::new(7.0, 5.0)).t, 1.0);
+    }
+
+    #[test]
+    fn test_project_spline() {
+        let spline = Spline::new(
+            vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 2.0),
+                Point::new(4.0, 0.0),
+                Point::new(6.0, 2.0),
+            ],
+            SplineKind::Centripetal,
+        );
+        for p in [
+            Point::new(2.0, 3.0),
+            Point::new(3.0, 0.0),
+            Point::new(5.0, 3.0),
+        ] {
+            let (nearest, _) = nearest_sample(|t| spline.point_at(t), p);
+            let projection = spline.project(p);
+            assert!((projection.t - nearest.t).abs() < 1e-3);
+            assert!(projection.point.distance(spline.point_at(projection.t)) < 1e-9);
+            assert!(projection.distance <= nearest.distance + 1e-9);
+        }
+    }
+}
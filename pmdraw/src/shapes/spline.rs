@@ -0,0 +1,607 @@
+use pmmath::{matrix::Mat, transform::Transform2};
+
+use super::{
+    bezier::{Bezier, TOLERANCE},
+    lengths_every,
+    line::Line,
+    offset,
+    point::Point,
+    projection::Projection,
+};
+
+/// How tangents at fit points are decided
+#[derive(Clone, Copy)]
+pub enum SplineKind {
+    /// curvature is continuous, and 0 at the origin and the end
+    Natural,
+    /// curvature is continuous, and tangents at the origin and the end are the directions
+    Clamped { start: Point, end: Point },
+    /// tangent at each fit point is parallel to the line between its neighbors
+    CatmullRom,
+    /// catmull-rom spline whose parameter grows with the square root of distances between fit points,
+    /// which never makes cusps or loops within a segment
+    Centripetal,
+}
+
+/// Piecewise cubic bezier curve which passes through fit points
+///
+/// unlike a single bezier of many fit points, it does not oscillate between fit points
+#[derive(Clone)]
+pub struct Spline {
+    /// fit points - the curve passes through them in order
+    pub fit_points: Vec<Point>,
+    pub kind: SplineKind,
+    /// origin, control points, and end of each cubic segment, where segments share their edges
+    ctrl_points: Vec<Point>,
+    /// cubic bezier between each pair of adjacent fit points
+    segments: Vec<Bezier>,
+}
+
+impl Spline {
+    /// dB/dt where t is the parameter of `point_at`
+    pub fn derivative(&self, t: f64) -> Point {
+        let (i, local_t) = self.segment_at(t);
+        self.segments.len() as f64 * self.segments[i].derivative(local_t)
+    }
+
+    pub fn end(&self) -> Point {
+        self.ctrl_points[self.ctrl_points.len() - 1]
+    }
+
+    pub fn len(&self) -> f64 {
+        self.segments.iter().map(|b| b.len()).sum()
+    }
+
+    pub fn mirror(&self, mirror_line: Line) -> Spline {
+        let mut mirrored = self.clone();
+        mirrored.transform(&Transform2::mirror(
+            mirror_line.origin.x,
+            mirror_line.origin.y,
+            mirror_line.end.x,
+            mirror_line.end.y,
+        ));
+        mirrored
+    }
+
+    /// Interpolate fit points by cubic bezier curves
+    pub fn new(fit_points: Vec<Point>, kind: SplineKind) -> Spline {
+        assert!(
+            fit_points.len() >= 2,
+            "Spline needs two fit points at least"
+        );
+        let (knots, tangents) = match kind {
+            SplineKind::Natural => {
+                let knots = knots(&fit_points, 1.0);
+                let tangents = solve_tangents(&fit_points, &knots, None);
+                (knots, tangents)
+            }
+            SplineKind::Clamped { start, end } => {
+                let knots = knots(&fit_points, 1.0);
+                let tangents = solve_tangents(
+                    &fit_points,
+                    &knots,
+                    Some((start.normalize(), end.normalize())),
+                );
+                (knots, tangents)
+            }
+            SplineKind::CatmullRom => {
+                let knots = knots(&fit_points, 0.0);
+                let tangents = catmull_rom_tangents(&fit_points, &knots);
+                (knots, tangents)
+            }
+            SplineKind::Centripetal => {
+                let knots = knots(&fit_points, 0.5);
+                let tangents = catmull_rom_tangents(&fit_points, &knots);
+                (knots, tangents)
+            }
+        };
+        // hermite curve of each segment is the cubic bezier of
+        //  Pi, Pi + h Di / 3, Pi+1 - h Di+1 / 3, Pi+1
+        let mut ctrl_points = vec![fit_points[0]];
+        for i in 0..fit_points.len() - 1 {
+            let h = knots[i + 1] - knots[i];
+            ctrl_points.push(fit_points[i] + h / 3.0 * tangents[i]);
+            ctrl_points.push(fit_points[i + 1] - h / 3.0 * tangents[i + 1]);
+            ctrl_points.push(fit_points[i + 1]);
+        }
+        Spline::from_ctrl_points(fit_points, kind, ctrl_points)
+    }
+
+    /// Curves at the distance from the curve within the tolerance, like `Bezier::offset`
+    pub fn offset(&self, distance: f64, tolerance: f64) -> Vec<Bezier> {
+        offset::offset(&self.segments, distance, tolerance)
+    }
+
+    pub fn origin(&self) -> Point {
+        self.ctrl_points[0]
+    }
+
+    /// Curves at the distance on the left and the right of the curve, within `bezier::TOLERANCE`
+    ///
+    /// each side is a spline of the cubic curves of `offset`, whose fit points are their edges
+    pub fn parallel(&self, distance: f64) -> Parallel {
+        let make_parallel = |distance: f64| {
+            let pieces = self.offset(distance, TOLERANCE);
+            let mut fit_points = vec![pieces[0].origin()];
+            let mut ctrl_points = vec![pieces[0].origin()];
+            for b in &pieces {
+                fit_points.push(b.end());
+                ctrl_points.extend(b.ctrl_points()[1..].to_vec());
+            }
+            Spline::from_ctrl_points(fit_points, self.kind, ctrl_points)
+        };
+        Parallel {
+            left: make_parallel(-distance),
+            right: make_parallel(distance),
+        }
+    }
+
+    /// Point at the length along the curve from the origin
+    pub fn point_at_length(&self, length: f64) -> Point {
+        let (i, local_t) = self.segment_at_length(length);
+        self.segments[i].point_at(local_t)
+    }
+
+    /// Points at even intervals of length along the curve from the origin
+    pub fn points_every_length(&self, interval: f64) -> Vec<Point> {
+        lengths_every(self.len(), interval)
+            .into_iter()
+            .map(|length| self.point_at_length(length))
+            .collect()
+    }
+
+    /// Point on the curve, where t from 0 to 1 is divided evenly into segments
+    pub fn point_at(&self, t: f64) -> Point {
+        let (i, local_t) = self.segment_at(t);
+        self.segments[i].point_at(local_t)
+    }
+
+    /// Nearest point on the curve to p, where t is what `point_at` takes
+    pub fn project(&self, p: Point) -> Projection {
+        let count_segments = self.segments.len() as f64;
+        Projection::nearest(self.segments.iter().enumerate().map(|(i, b)| {
+            let projection = b.project(p);
+            Projection {
+                t: (i as f64 + projection.t) / count_segments,
+                ..projection
+            }
+        }))
+        .expect("Spline has one segment at least")
+    }
+
+    pub fn reverse(&mut self) {
+        self.fit_points.reverse();
+        self.ctrl_points.reverse();
+        self.segments.reverse();
+        for b in &mut self.segments {
+            b.reverse();
+        }
+        if let SplineKind::Clamped { start, end } = self.kind {
+            self.kind = SplineKind::Clamped {
+                start: -1.0 * end,
+                end: -1.0 * start,
+            };
+        }
+    }
+
+    /// Rotate around point
+    pub fn rotate(&mut self, angle_degree: f64, around: Point) {
+        self.transform(&Transform2::rotate_around(angle_degree, around.x, around.y));
+    }
+
+    /// cubic bezier curves between adjacent fit points
+    pub fn segments(&self) -> &Vec<Bezier> {
+        &self.segments
+    }
+
+    /// Split the curve at the point on it and get two curves
+    pub fn split(&self, p: Point) -> Split {
+        // find the segment nearest to p
+        let (i, local_t, _) = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(i, b)| {
+                let t = b.solve_t_at(p);
+                (i, t, b.point_at(t).distance(p))
+            })
+            .fold((0, 0.0, f64::INFINITY), |nearest, each| {
+                if each.2 < nearest.2 {
+                    each
+                } else {
+                    nearest
+                }
+            });
+        self.split_segment(i, local_t.clamp(0.0, 1.0))
+    }
+
+    /// Split the curve at the length along the curve from the origin
+    pub fn split_at_length(&self, length: f64) -> Split {
+        let (i, local_t) = self.segment_at_length(length);
+        self.split_segment(i, local_t)
+    }
+
+    /// Split the curve at t, which is what `point_at` takes
+    pub fn split_at_t(&self, t: f64) -> Split {
+        let (i, local_t) = self.segment_at(t);
+        self.split_segment(i, local_t)
+    }
+
+    /// Part of the curve from t0 to t1, which are what `point_at` takes and t0 <= t1
+    pub fn sub_curve(&self, t0: f64, t1: f64) -> Spline {
+        let (i, local_t0) = self.segment_at(t0);
+        let (j, local_t1) = self.segment_at(t1);
+        // t1 on the knot is the end of the previous segment, not to leave a segment of length 0 after it
+        let (j, local_t1) = if j > i && local_t1 == 0.0 {
+            (j - 1, 1.0)
+        } else {
+            (j, local_t1)
+        };
+        // t1 in the part after t0, whose first segment is the rest of the segment i
+        let local_t1 = if j == i && local_t0 < 1.0 {
+            (local_t1 - local_t0) / (1.0 - local_t0)
+        } else {
+            local_t1
+        };
+        self.split_segment(i, local_t0)
+            .snd
+            .split_segment(j - i, local_t1)
+            .fst
+    }
+
+    /// t of the point at the length along the curve from the origin, which is what `point_at` takes
+    pub fn t_at_length(&self, length: f64) -> f64 {
+        let (i, local_t) = self.segment_at_length(length);
+        (i as f64 + local_t) / self.segments.len() as f64
+    }
+
+    /// Unit tangent at the length along the curve from the origin
+    pub fn tangent_at_length(&self, length: f64) -> Point {
+        let (i, local_t) = self.segment_at_length(length);
+        self.segments[i].tangent(local_t)
+    }
+
+    pub fn to(&mut self, dx: f64, dy: f64) {
+        self.transform(&Transform2::translate(dx, dy));
+    }
+
+    /// Transform by an affine transformation
+    pub fn transform(&mut self, m: &Transform2) {
+        for p in &mut self.fit_points {
+            *p = p.transform(m);
+        }
+        for p in &mut self.ctrl_points {
+            *p = p.transform(m);
+        }
+        for b in &mut self.segments {
+            b.transform(m);
+        }
+        if let SplineKind::Clamped { start, end } = self.kind {
+            let direction = |v: Point| {
+                let (x, y) = m.apply_vector(v.x, v.y);
+                Point::new(x, y)
+            };
+            self.kind = SplineKind::Clamped {
+                start: direction(start),
+                end: direction(end),
+            };
+        }
+    }
+
+    fn from_ctrl_points(
+        fit_points: Vec<Point>,
+        kind: SplineKind,
+        ctrl_points: Vec<Point>,
+    ) -> Spline {
+        let segments = ctrl_points
+            .windows(4)
+            .step_by(3)
+            .map(|points| Bezier::new_with_ctrl_points(points.to_vec()))
+            .collect();
+        Spline {
+            fit_points,
+            kind,
+            ctrl_points,
+            segments,
+        }
+    }
+
+    /// Index of the segment and t in the segment at the length from the origin
+    fn segment_at_length(&self, length: f64) -> (usize, f64) {
+        let mut remaining = length;
+        for (i, b) in self.segments.iter().enumerate() {
+            let len = b.len();
+            if remaining <= len || i == self.segments.len() - 1 {
+                // subtracting lengths of segments may leave slightly more than the last one by rounding errors
+                let remaining = if remaining <= len + TOLERANCE {
+                    remaining.min(len)
+                } else {
+                    remaining
+                };
+                return (i, b.t_at_length(remaining));
+            }
+            remaining -= len;
+        }
+        unreachable!("Spline has one segment at least")
+    }
+
+    /// Index of the segment and t in the segment
+    fn segment_at(&self, t: f64) -> (usize, f64) {
+        let count_segments = self.segments.len();
+        let scaled_t = t * count_segments as f64;
+        let i = (scaled_t.floor().max(0.0) as usize).min(count_segments - 1);
+        (i, scaled_t - i as f64)
+    }
+
+    /// Split the segment at t in it by de casteljau's algorithm
+    fn split_segment(&self, i: usize, local_t: f64) -> Split {
+        let b = &self.ctrl_points[3 * i..3 * i + 4];
+        let lerp = |p0: Point, p1: Point| p0 + local_t * (p1 - p0);
+        let (b01, b12, b23) = (lerp(b[0], b[1]), lerp(b[1], b[2]), lerp(b[2], b[3]));
+        let (b012, b123) = (lerp(b01, b12), lerp(b12, b23));
+        let b0123 = lerp(b012, b123);
+        let mut fst_ctrl_points = self.ctrl_points[..3 * i + 1].to_vec();
+        fst_ctrl_points.extend(vec![b01, b012, b0123]);
+        let mut snd_ctrl_points = vec![b0123, b123, b23];
+        snd_ctrl_points.extend(self.ctrl_points[3 * i + 3..].to_vec());
+        let mut fst_fit_points = self.fit_points[..i + 1].to_vec();
+        fst_fit_points.push(b0123);
+        let mut snd_fit_points = vec![b0123];
+        snd_fit_points.extend(self.fit_points[i + 1..].to_vec());
+        // tangents at the split point clamp each part
+        let (fst_kind, snd_kind) = match self.kind {
+            SplineKind::Clamped { start, end } => (
+                SplineKind::Clamped {
+                    start,
+                    end: b123 - b012,
+                },
+                SplineKind::Clamped {
+                    start: b123 - b012,
+                    end,
+                },
+            ),
+            kind => (kind, kind),
+        };
+        Split {
+            fst: Spline::from_ctrl_points(fst_fit_points, fst_kind, fst_ctrl_points),
+            snd: Spline::from_ctrl_points(snd_fit_points, snd_kind, snd_ctrl_points),
+        }
+    }
+}
+
+/// Two splines made by splitting a spline, before and after the point where it is split
+pub struct Split {
+    pub fst: Spline,
+    pub snd: Spline,
+}
+
+pub struct Parallel {
+    pub left: Spline,
+    pub right: Spline,
+}
+
+/// Tangents of catmull-rom spline of non-uniform knots
+///
+/// tangents at the origin and the end point to their neighbors
+fn catmull_rom_tangents(points: &[Point], knots: &[f64]) -> Vec<Point> {
+    let n = points.len();
+    let chord = |i: usize, j: usize| (points[j] - points[i]) / (knots[j] - knots[i]);
+    (0..n)
+        .map(|i| {
+            if i == 0 {
+                chord(0, 1)
+            } else if i == n - 1 {
+                chord(n - 2, n - 1)
+            } else {
+                chord(i - 1, i) - chord(i - 1, i + 1) + chord(i, i + 1)
+            }
+        })
+        .collect()
+}
+
+/// Parameters of fit points which grow with distances to the power of alpha
+///
+/// alpha is 0 for uniform, 0.5 for centripetal, and 1 for chord length.
+/// coincident fit points are 1 apart like uniform knots, not to divide tangents by 0
+fn knots(points: &[Point], alpha: f64) -> Vec<f64> {
+    let mut knots = vec![0.0];
+    for i in 1..points.len() {
+        let distance = points[i - 1].distance(points[i]);
+        let interval = if distance > 0.0 {
+            distance.powf(alpha)
+        } else {
+            1.0
+        };
+        knots.push(knots[i - 1] + interval);
+    }
+    knots
+}
+
+/// Tangents of cubic spline whose second derivatives are continuous
+///
+/// - ends - tangents at the origin and the end, or none for natural spline
+fn solve_tangents(points: &[Point], knots: &[f64], ends: Option<(Point, Point)>) -> Vec<Point> {
+    let n = points.len();
+    let h = |i: usize| knots[i + 1] - knots[i];
+    let slope = |i: usize| (points[i + 1] - points[i]) / h(i);
+    // tridiagonal system of tangents, solved for x and y at once
+    let mut a = Mat::zeros(n, n);
+    let mut b = Mat::zeros(n, 2);
+    let mut set_row = |i: usize, rhs: Point| {
+        b[i][0] = rhs.x;
+        b[i][1] = rhs.y;
+    };
+    match ends {
+        Some((start, end)) => {
+            a[0][0] = 1.0;
+            set_row(0, start);
+            a[n - 1][n - 1] = 1.0;
+            set_row(n - 1, end);
+        }
+        None => {
+            // second derivatives are 0 at the origin and the end
+            a[0][0] = 2.0;
+            a[0][1] = 1.0;
+            set_row(0, 3.0 * slope(0));
+            a[n - 1][n - 2] = 1.0;
+            a[n - 1][n - 1] = 2.0;
+            set_row(n - 1, 3.0 * slope(n - 2));
+        }
+    }
+    for i in 1..n - 1 {
+        a[i][i - 1] = h(i);
+        a[i][i] = 2.0 * (h(i - 1) + h(i));
+        a[i][i + 1] = h(i - 1);
+        set_row(i, 3.0 * (h(i) * slope(i - 1) + h(i - 1) * slope(i)));
+    }
+    let tangents = a.solve(&b).expect("Fit points of spline must be distinct");
+    (0..n)
+        .map(|i| Point::new(tangents[i][0], tangents[i][1]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test_spline {
+    use crate::shapes::{
+        bezier::TOLERANCE,
+        point::Point,
+        spline::{Spline, SplineKind},
+    };
+
+    fn fit_points() -> Vec<Point> {
+        vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 2.0),
+            Point::new(3.0, 2.5),
+            Point::new(4.0, 0.5),
+            Point::new(7.0, 1.0),
+        ]
+    }
+
+    fn kinds() -> Vec<SplineKind> {
+        vec![
+            SplineKind::Natural,
+            SplineKind::Clamped {
+                start: Point::new(0.0, 1.0),
+                end: Point::new(1.0, 0.0),
+            },
+            SplineKind::CatmullRom,
+            SplineKind::Centripetal,
+        ]
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let points = fit_points();
+        for kind in kinds() {
+            let spline = Spline::new(points.clone(), kind);
+            for (i, p) in points.iter().enumerate() {
+                assert!(spline.point_at(i as f64 / 4.0).distance(*p) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_continuity() {
+        for kind in kinds() {
+            let spline = Spline::new(fit_points(), kind);
+            let segments = spline.segments();
+            for i in 0..segments.len() - 1 {
+                let (fst, snd) = (&segments[i], &segments[i + 1]);
+                // tangents are the same on both sides of each joint
+                assert!(fst.tangent(1.0).distance(snd.tangent(0.0)) < 1e-9);
+                // so is curvature for splines whose second derivatives are continuous
+                if let SplineKind::Natural | SplineKind::Clamped { .. } = kind {
+                    assert!((fst.curvature(1.0) - snd.curvature(0.0)).abs() < 1e-9);
+                }
+            }
+        }
+        // natural spline is straight at the origin and the end
+        let natural = Spline::new(fit_points(), SplineKind::Natural);
+        let segments = natural.segments();
+        assert!(segments[0].curvature(0.0).abs() < 1e-9);
+        assert!(segments[3].curvature(1.0).abs() < 1e-9);
+        // clamped spline starts and ends in the directions
+        let clamped = Spline::new(fit_points(), kinds()[1]);
+        let segments = clamped.segments();
+        assert!(segments[0].tangent(0.0).distance(Point::new(0.0, 1.0)) < 1e-9);
+        assert!(segments[3].tangent(1.0).distance(Point::new(1.0, 0.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_sub_curve_on_knot() {
+        let spline = Spline::new(fit_points(), SplineKind::Natural);
+        // from the middle of the first segment to the end of the second one
+        let sub_curve = spline.sub_curve(0.125, 0.5);
+        assert_eq!(sub_curve.segments().len(), 2);
+        assert!(sub_curve.origin().distance(spline.point_at(0.125)) < 1e-9);
+        assert!(sub_curve.end().distance(fit_points()[2]) < 1e-9);
+        assert!(
+            (sub_curve.len() - spline.segments()[1].len() - spline.sub_curve(0.125, 0.25).len())
+                .abs()
+                < TOLERANCE
+        );
+        // from knot to knot
+        let sub_curve = spline.sub_curve(0.25, 0.75);
+        assert_eq!(sub_curve.segments().len(), 2);
+        for b in sub_curve.segments() {
+            assert!(b.len() > 1.0);
+        }
+    }
+
+    #[test]
+    fn test_coincident_fit_points() {
+        let mut points = fit_points();
+        points.insert(2, points[1]);
+        for kind in kinds() {
+            let spline = Spline::new(points.clone(), kind);
+            for i in 0..=50 {
+                let p = spline.point_at(i as f64 / 50.0);
+                assert!(p.x.is_finite() && p.y.is_finite());
+            }
+            for (i, p) in points.iter().enumerate() {
+                assert!(spline.point_at(i as f64 / 5.0).distance(*p) < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parallel() {
+        let spline = Spline::new(fit_points(), SplineKind::Centripetal);
+        let parallel = spline.parallel(0.5);
+        for side in [&parallel.left, &parallel.right] {
+            for i in 0..=20 {
+                let p = side.point_at(i as f64 / 20.0);
+                assert!((spline.project(p).distance - 0.5).abs() < 2.0 * TOLERANCE);
+            }
+        }
+        // left is on the left of the direction of the curve
+        let normal = spline.segments()[0].normal(0.0);
+        assert!(
+            parallel
+                .left
+                .origin()
+                .distance(spline.origin() + 0.5 * normal)
+                < 1e-9
+        );
+        assert!(
+            parallel
+                .right
+                .origin()
+                .distance(spline.origin() - 0.5 * normal)
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn test_t_at_length() {
+        let spline = Spline::new(fit_points(), SplineKind::Centripetal);
+        // t of the length of the curve up to t is t itself, across the knots
+        for i in 1..20 {
+            let t = i as f64 / 20.0;
+            assert!((spline.t_at_length(spline.sub_curve(0.0, t).len()) - t).abs() < TOLERANCE);
+        }
+        assert_eq!(spline.t_at_length(0.0), 0.0);
+        assert!((spline.t_at_length(spline.len()) - 1.0).abs() < TOLERANCE);
+    }
+}
@@ -1,2 +1,3 @@
+pub mod page;
 pub mod pdf;
 pub mod pdf2;
@@ -0,0 +1,155 @@
+use pmdraw::{
+    drawing::Drawing,
+    shapes::{
+        geometry::{BoundingBox, POINT_RADIUS},
+        line::Line,
+        point::Point,
+    },
+};
+
+// width in pdf point
+const A3_WIDTH: f32 = 842.0;
+// height in pdf point
+const A3_HEIGHT: f32 = 1190.0;
+/// pdf points in a centimeter
+const PT_PER_CM: f64 = 28.345175603955806;
+
+/// Lines between adjacent points in pdf points on the page at the offset
+pub fn page_lines(points: &[Point], offset_x: f32, offset_y: f32) -> Vec<Line> {
+    points
+        .windows(2)
+        .map(|points| {
+            let to_page = |p: Point| to_pt_point(p).to(-offset_x as f64, -offset_y as f64);
+            Line::new(to_page(points[0]), to_page(points[1]))
+        })
+        .collect()
+}
+
+/// Offsets in pdf points of pages covering all the shapes of the drawing, row by row from the bottom
+///
+/// the shapes are widened by the radius of points not to cut points marked on the edges,
+/// and a page at the origin is given if nothing is drawn
+pub fn page_offsets(drawing: &Drawing, paper_width: f32, paper_height: f32) -> Vec<(f32, f32)> {
+    let bounding_box = match drawing.bounding_box() {
+        Some(bounding_box) => bounding_box,
+        None => return vec![(0.0, 0.0)],
+    };
+    let min = to_pt_point(bounding_box.min.to(-POINT_RADIUS, -POINT_RADIUS));
+    let max = to_pt_point(bounding_box.max.to(POINT_RADIUS, POINT_RADIUS));
+    let count = |extent: f64, paper: f32| (extent / paper as f64).ceil().max(1.0) as usize;
+    let (columns, rows) = (
+        count(max.x - min.x, paper_width),
+        count(max.y - min.y, paper_height),
+    );
+    (0..rows)
+        .flat_map(|i| {
+            (0..columns).map(move |j| {
+                (
+                    min.x as f32 + j as f32 * paper_width,
+                    min.y as f32 + i as f32 * paper_height,
+                )
+            })
+        })
+        .collect()
+}
+
+/// Region of the drawing on the page in centimeters, which is widened by the radius of points
+/// not to miss points marked on the edges of the page
+pub fn page_region(
+    offset_x: f32,
+    offset_y: f32,
+    paper_width: f32,
+    paper_height: f32,
+) -> BoundingBox {
+    let to_cm = |pt: f32| pt as f64 / PT_PER_CM;
+    BoundingBox {
+        min: Point::new(to_cm(offset_x), to_cm(offset_y)).to(-POINT_RADIUS, -POINT_RADIUS),
+        max: Point::new(
+            to_cm(offset_x + paper_width),
+            to_cm(offset_y + paper_height),
+        )
+        .to(POINT_RADIUS, POINT_RADIUS),
+    }
+}
+
+/// Width and height of a page in pdf points, which is A3 unless they are given
+/// - paper_width - width of document in millimeter
+/// - paper_height - height of document in millimeter
+pub fn paper_size(paper_width: Option<f64>, paper_height: Option<f64>) -> (f32, f32) {
+    let paper_width = match paper_width {
+        Some(custom_width) => to_pt(custom_width / 10.0),
+        None => A3_WIDTH,
+    };
+    let paper_height = match paper_height {
+        Some(custom_height) => to_pt(custom_height / 10.0),
+        None => A3_HEIGHT,
+    };
+    (paper_width, paper_height)
+}
+
+fn to_pt(centimeter: f64) -> f32 {
+    centimeter as f32 * PT_PER_CM as f32
+}
+
+fn to_pt_point(p_centimeter: Point) -> Point {
+    p_centimeter * PT_PER_CM
+}
+
+#[cfg(test)]
+mod test_page {
+    use pmdraw::{
+        drawing::Drawing,
+        shapes::{geometry::POINT_RADIUS, line::Line, point::Point},
+    };
+
+    use crate::page::{page_offsets, page_region, PT_PER_CM};
+
+    #[test]
+    fn test_page_offsets_empty() {
+        let drawing = Drawing::new(10.0, 10.0);
+        assert_eq!(page_offsets(&drawing, 100.0, 200.0), vec![(0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_page_offsets() {
+        // 10 by 4 centimeters, which is 2 by 1 pages of 8 by 8 centimeters with points on the edges
+        let mut drawing = Drawing::new(20.0, 20.0);
+        drawing.line(Line::new(Point::new(3.0, 1.0), Point::new(13.0, 5.0)));
+        let paper = (8.0 * PT_PER_CM) as f32;
+        let offsets = page_offsets(&drawing, paper, paper);
+        assert_eq!(offsets.len(), 2);
+        let origin = (3.0 - POINT_RADIUS) * PT_PER_CM;
+        assert!((offsets[0].0 as f64 - origin).abs() < 1e-3);
+        assert!((offsets[0].1 as f64 - (1.0 - POINT_RADIUS) * PT_PER_CM).abs() < 1e-3);
+        assert!(offsets[1].0 - offsets[0].0 == paper && offsets[1].1 == offsets[0].1);
+        // regions of the pages cover the line, rows from the bottom
+        let mut drawing = Drawing::new(20.0, 20.0);
+        drawing.line(Line::new(Point::new(0.0, 0.0), Point::new(5.0, 20.0)));
+        let offsets = page_offsets(&drawing, paper, paper);
+        assert_eq!(offsets.len(), 3);
+        assert!(offsets.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        for k in 0..=20 {
+            let p = Point::new(0.25 * k as f64, k as f64);
+            assert!(offsets.iter().any(|(x, y)| {
+                let region = page_region(*x, *y, paper, paper);
+                region.min.x <= p.x
+                    && p.x <= region.max.x
+                    && region.min.y <= p.y
+                    && p.y <= region.max.y
+            }));
+        }
+    }
+
+    #[test]
+    fn test_page_region() {
+        let region = page_region(0.0, (10.0 * PT_PER_CM) as f32, 100.0, 200.0);
+        assert!(
+            region
+                .min
+                .distance(Point::new(-POINT_RADIUS, 10.0 - POINT_RADIUS))
+                < 1e-4
+        );
+        let max = Point::new(100.0 / PT_PER_CM, 10.0 + 200.0 / PT_PER_CM);
+        assert!(region.max.distance(max.to(POINT_RADIUS, POINT_RADIUS)) < 1e-4);
+    }
+}
@@ -12,9 +12,20 @@ pub fn binomial(n: usize, k: usize) -> u32 {
     pascal_triangle[n][k]
 }
 
+/// nC0, nC1, ..., nCn
+///
+/// computed in f64 without building pascal's triangle, hence it does not overflow for large n
+pub fn binomial_row(n: usize) -> Vec<f64> {
+    let mut row = vec![1.0; n + 1];
+    for k in 1..=n {
+        row[k] = row[k - 1] * (n - k + 1) as f64 / k as f64;
+    }
+    row
+}
+
 #[cfg(test)]
 mod test {
-    use crate::binomial::binomial;
+    use crate::binomial::{binomial, binomial_row};
     #[test]
     fn test_binomial() {
         assert_eq!(binomial(3, 1), 3);
@@ -25,4 +36,15 @@ mod test {
         assert_eq!(binomial(4, 4), 1);
         assert_eq!(binomial(7, 2), 21);
     }
+
+    #[test]
+    fn test_binomial_row() {
+        assert_eq!(binomial_row(0), vec![1.0]);
+        assert_eq!(binomial_row(4), vec![1.0, 4.0, 6.0, 4.0, 1.0]);
+        for k in 0..=8 {
+            assert_eq!(binomial_row(8)[k], binomial(8, k) as f64);
+        }
+        // 40C20 overflows u32
+        assert_eq!(binomial_row(40)[20], 137846528820.0);
+    }
 }
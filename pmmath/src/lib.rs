@@ -2,5 +2,12 @@
 extern crate impl_ops;
 
 pub mod binomial;
+pub mod lu;
 pub mod matrix;
+pub mod optimize;
+pub mod polynomial;
+pub mod qr;
+pub mod quadrature;
+pub mod roots;
 pub mod sigma;
+pub mod transform;
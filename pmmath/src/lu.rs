@@ -0,0 +1,220 @@
+use crate::matrix::{Mat, NotInvertible};
+
+/// LU factorization with partial pivoting such that P A = L U
+///
+/// - L - lower triangular matrix whose diagonal elements are 1
+/// - U - upper triangular matrix
+/// - P - permutation matrix which swaps rows of A
+pub struct Lu {
+    /// L below the diagonal and U on and above the diagonal, stored in row-major order
+    lu: Vec<f64>,
+    /// pivot[i] is the row of A which is moved to the i-th row
+    pivot: Vec<usize>,
+    /// 1.0 if the number of row swaps is even, otherwise -1.0
+    sign: f64,
+    /// size of the matrix
+    n: usize,
+    /// 1-norm of A used to estimate condition number
+    norm: f64,
+}
+
+impl Lu {
+    /// Estimate the condition number of A in 1-norm, ||A|| ||A^-1||
+    ///
+    /// ||A^-1|| is estimated by Hager's algorithm, which needs a few solves instead of the inverse matrix.
+    /// Large values (say 1e10 or more) mean that solutions of A x = b are not reliable.
+    pub fn condition_estimate(&self) -> f64 {
+        let n = self.n;
+        let mut x = vec![1.0 / n as f64; n];
+        let mut inverse_norm = 0.0;
+        for _ in 0..5 {
+            let y = self.solve_vec(&x);
+            inverse_norm = y.iter().map(|y| y.abs()).sum();
+            let xi = y
+                .iter()
+                .map(|y| if *y >= 0.0 { 1.0 } else { -1.0 })
+                .collect::<Vec<f64>>();
+            let z = self.solve_transposed_vec(&xi);
+            let (j, z_max) = z.iter().enumerate().fold((0, 0.0), |(j, max), (i, z)| {
+                if z.abs() > max {
+                    (i, z.abs())
+                } else {
+                    (j, max)
+                }
+            });
+            let z_dot_x: f64 = z.iter().zip(x.iter()).map(|(z, x)| z * x).sum();
+            if z_max <= z_dot_x {
+                break;
+            }
+            x = vec![0.0; n];
+            x[j] = 1.0;
+        }
+        self.norm * inverse_norm
+    }
+
+    /// det(A) = det(P) * product of diagonal elements of U
+    pub fn determinant(&self) -> f64 {
+        (0..self.n).fold(self.sign, |det, i| det * self.lu[i * self.n + i])
+    }
+
+    /// Solve A X = B for each column of B
+    pub fn solve(&self, b: &Mat) -> Mat {
+        assert_eq!(b.count_rows, self.n, "Rows of b must be the same as A");
+        let mut x = Mat::zeros(b.count_rows, b.count_columns);
+        for j in 0..b.count_columns {
+            let column = (0..self.n).map(|i| b[i][j]).collect::<Vec<f64>>();
+            let solution = self.solve_vec(&column);
+            for i in 0..self.n {
+                x[i][j] = solution[i];
+            }
+        }
+        x
+    }
+
+    /// Solve A x = b where b is a vector
+    pub fn solve_vec(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        // forward substitution L y = P b
+        let mut x = self.pivot.iter().map(|&i| b[i]).collect::<Vec<f64>>();
+        for i in 0..n {
+            for k in 0..i {
+                x[i] -= self.lu[i * n + k] * x[k];
+            }
+        }
+        // backward substitution U x = y
+        for i in (0..n).rev() {
+            for k in i + 1..n {
+                x[i] -= self.lu[i * n + k] * x[k];
+            }
+            x[i] /= self.lu[i * n + i];
+        }
+        x
+    }
+
+    /// Solve A^T x = b, that is U^T L^T P x = b
+    fn solve_transposed_vec(&self, b: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        // forward substitution U^T w = b
+        let mut w = b.to_vec();
+        for i in 0..n {
+            for k in 0..i {
+                w[i] -= self.lu[k * n + i] * w[k];
+            }
+            w[i] /= self.lu[i * n + i];
+        }
+        // backward substitution L^T v = w
+        for i in (0..n).rev() {
+            for k in i + 1..n {
+                w[i] -= self.lu[k * n + i] * w[k];
+            }
+        }
+        // x = P^T v
+        let mut x = vec![0.0; n];
+        for i in 0..n {
+            x[self.pivot[i]] = w[i];
+        }
+        x
+    }
+}
+
+impl Mat {
+    /// det(A), which is 0 if A is singular
+    pub fn determinant(&self) -> f64 {
+        match self.lu() {
+            Ok(lu) => lu.determinant(),
+            Err(NotInvertible) => 0.0,
+        }
+    }
+
+    /// Factorize n by n matrix A into P A = L U by gaussian elimination with partial pivoting
+    pub fn lu(&self) -> Result<Lu, NotInvertible> {
+        assert_eq!(
+            self.count_rows, self.count_columns,
+            "A matrix must be n by n"
+        );
+        let n = self.count_rows;
+        let mut lu = self.as_slice().to_vec();
+        let mut pivot = (0..n).collect::<Vec<usize>>();
+        let mut sign = 1.0;
+        let norm = (0..n)
+            .map(|j| (0..n).map(|i| self[i][j].abs()).sum::<f64>())
+            .fold(0.0, f64::max);
+        for j in 0..n {
+            // choose the largest element in the column as pivot to reduce rounding errors
+            let mut max_row = j;
+            for i in j + 1..n {
+                if lu[i * n + j].abs() > lu[max_row * n + j].abs() {
+                    max_row = i;
+                }
+            }
+            if lu[max_row * n + j] == 0.0 {
+                return Err(NotInvertible);
+            }
+            if max_row != j {
+                for k in 0..n {
+                    lu.swap(j * n + k, max_row * n + k);
+                }
+                pivot.swap(j, max_row);
+                sign = -sign;
+            }
+            let diagonal = lu[j * n + j];
+            for i in j + 1..n {
+                let l = lu[i * n + j] / diagonal;
+                lu[i * n + j] = l;
+                for k in j + 1..n {
+                    lu[i * n + k] -= l * lu[j * n + k];
+                }
+            }
+        }
+        Ok(Lu {
+            lu,
+            pivot,
+            sign,
+            n,
+            norm,
+        })
+    }
+
+    /// Solve A X = B by LU factorization without calculating inverse matrix
+    pub fn solve(&self, b: &Mat) -> Result<Mat, NotInvertible> {
+        Ok(self.lu()?.solve(b))
+    }
+}
+
+#[cfg(test)]
+mod test_lu {
+    use crate::matrix::Mat;
+
+    #[test]
+    fn test_lu() {
+        // the first pivot is tiny, which breaks gaussian elimination without pivoting
+        let a = Mat::from_rows(vec![
+            vec![1e-20, 1.0, 2.0],
+            vec![1.0, 1.0, 1.0],
+            vec![2.0, 0.5, 3.0],
+        ]);
+        let b = Mat::from_rows(vec![vec![3.0], vec![3.0], vec![5.5]]);
+        let x = a.solve(&b).expect("Failed to solve");
+        for i in 0..3 {
+            assert!((x[i][0] - 1.0).abs() < 1e-12);
+        }
+        let lu = a.lu().expect("Failed to factorize");
+        assert!((lu.determinant() - (-4.0)).abs() < 1e-12);
+        assert!(lu.condition_estimate() < 100.0);
+    }
+
+    #[test]
+    fn test_lu_singular() {
+        let a = Mat::from_rows(vec![vec![1.0, 2.0], vec![2.0, 4.0]]);
+        assert!(a.lu().is_err());
+        assert_eq!(a.determinant(), 0.0);
+        // Hilbert matrix is famously ill-conditioned
+        let hilbert = Mat::from_rows(
+            (0..10)
+                .map(|i| (0..10).map(|j| 1.0 / (i + j + 1) as f64).collect())
+                .collect(),
+        );
+        let lu = hilbert.lu().expect("Failed to factorize");
+        assert!(lu.condition_estimate() > 1e12);
+    }
+}
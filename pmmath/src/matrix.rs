@@ -5,22 +5,24 @@ use crate::sigma::sigma;
 
 #[derive(Debug, Clone)]
 pub struct NotInvertible;
+
+/// m by n matrix whose elements are stored contiguously in row-major order
+#[derive(Debug, Clone)]
 pub struct Mat {
-    pub row_vecs: Vec<Vec<f64>>,
+    elements: Vec<f64>,
     pub count_columns: usize,
     pub count_rows: usize,
 }
 
 impl Mat {
-    pub fn clone(&self) -> Mat {
-        let mut mat = Mat::new(self.count_rows, self.count_columns);
-        mat.for_each(|mat, i, j| mat[i][j] = self[i][j]);
-        mat
+    /// Elements in row-major order
+    pub fn as_slice(&self) -> &[f64] {
+        &self.elements
     }
 
     pub fn for_each<T>(&mut self, do_something: T)
     where
-        T: Fn(&mut Mat, usize, usize) -> (),
+        T: Fn(&mut Mat, usize, usize),
     {
         for i in 0..self.count_rows {
             for j in 0..self.count_columns {
@@ -35,46 +37,7 @@ impl Mat {
             self.count_rows, self.count_columns,
             "A matrix must be n by n"
         );
-        // identity matrix
-        let mut inverse_mat = Mat::new(self.count_rows, self.count_columns);
-        let mut mat = self.clone();
-        // use gaussian elimination to calculate inverse matrix
-        for j in 0..self.count_columns {
-            for i in j..self.count_rows {
-                let element = mat[i][j];
-                if element != 0.0 {
-                    if i != j {
-                        mat.swap_row(i, j);
-                        inverse_mat.swap_row(i, j);
-                    }
-                    if element != 1.0 {
-                        mat.multiply_row(i, 1.0 / element);
-                        inverse_mat.multiply_row(i, 1.0 / element);
-                    }
-                    break;
-                }
-                if i == self.count_rows - 1 {
-                    return Err(NotInvertible);
-                }
-            }
-            for i in 0..self.count_rows {
-                if i != j {
-                    let element = mat[i][j];
-                    if element != 0.0 {
-                        mat.subtract_row(i, j, element);
-                        inverse_mat.subtract_row(i, j, element);
-                    }
-                }
-            }
-        }
-        Ok(inverse_mat)
-    }
-
-    /// Multiply a row with a scalar
-    fn multiply_row(&mut self, row: usize, scalar: f64) {
-        for k in 0..self.count_columns {
-            self[row][k] = self[row][k] * scalar;
-        }
+        self.solve(&Mat::new(self.count_rows, self.count_columns))
     }
 
     /// Init Identity matrix
@@ -88,44 +51,56 @@ impl Mat {
 
     /// Init diagonal m by n matrix
     pub fn new_diagonal(count_rows: usize, count_columns: usize, diagonal: Vec<f64>) -> Mat {
-        let row_vec: Vec<f64> = vec![0.0; count_columns];
-        let mut row_vecs = vec![row_vec; count_rows];
+        let mut mat = Mat::zeros(count_rows, count_columns);
         for i in 0..min(min(count_columns, count_rows), diagonal.len()) {
-            row_vecs[i][i] = diagonal[i];
+            mat[i][i] = diagonal[i];
+        }
+        mat
+    }
+
+    /// Init m by n matrix from its rows
+    pub fn from_rows(rows: Vec<Vec<f64>>) -> Mat {
+        let count_rows = rows.len();
+        let count_columns = if count_rows == 0 { 0 } else { rows[0].len() };
+        let mut elements = Vec::with_capacity(count_rows * count_columns);
+        for row in rows {
+            assert_eq!(row.len(), count_columns, "All rows must be the same size");
+            elements.extend(row);
         }
         Mat {
+            elements,
             count_columns,
             count_rows,
-            row_vecs,
         }
     }
 
-    /// Subtract a row by another multiplied by scalar
-    fn subtract_row(&mut self, row: usize, column: usize, scalar: f64) -> () {
-        for k in 0..self.count_columns {
-            self[row][k] = self[row][k] - self[column][k] * scalar;
-        }
+    /// Transposed n by m matrix
+    pub fn transpose(&self) -> Mat {
+        let mut mat = Mat::zeros(self.count_columns, self.count_rows);
+        mat.for_each(|mat, i, j| mat[i][j] = self[j][i]);
+        mat
     }
 
-    /// Swap rows
-    fn swap_row(&mut self, row: usize, another_row: usize) -> () {
-        for k in 0..self.count_columns {
-            let temp_row = self[row][k];
-            self[row][k] = self[another_row][k];
-            self[another_row][k] = temp_row;
+    /// Init m by n matrix filled with zero
+    pub fn zeros(count_rows: usize, count_columns: usize) -> Mat {
+        Mat {
+            elements: vec![0.0; count_rows * count_columns],
+            count_columns,
+            count_rows,
         }
     }
 }
 
+/// `mat[i]` is the i-th row, thus `mat[i][j]` is the element at row i and column j
 impl Index<usize> for Mat {
-    type Output = Vec<f64>;
-    fn index<'a>(&'a self, i: usize) -> &'a Vec<f64> {
-        &self.row_vecs[i]
+    type Output = [f64];
+    fn index(&self, i: usize) -> &[f64] {
+        &self.elements[i * self.count_columns..(i + 1) * self.count_columns]
     }
 }
 impl IndexMut<usize> for Mat {
-    fn index_mut<'a>(&'a mut self, i: usize) -> &'a mut Vec<f64> {
-        &mut self.row_vecs[i]
+    fn index_mut(&mut self, i: usize) -> &mut [f64] {
+        &mut self.elements[i * self.count_columns..(i + 1) * self.count_columns]
     }
 }
 impl_op_ex!(+|a: &Mat, b: &Mat| -> Mat {
@@ -177,9 +152,9 @@ mod test_matrix {
     fn test_matrix() {
         let mut identity = Mat::new(3, 3);
         let mut a = Mat::new(3, 3);
-        a[0] = vec![1.5, 0.2, 2.0];
-        a[1] = vec![0.5, 1.2, 1.0];
-        a[2] = vec![0.0, 0.5, 3.0];
+        a[0].copy_from_slice(&[1.5, 0.2, 2.0]);
+        a[1].copy_from_slice(&[0.5, 1.2, 1.0]);
+        a[2].copy_from_slice(&[0.0, 0.5, 3.0]);
         let a_inverse = a.inverse().expect("Failed to calculate inverse");
         let a_x_a_inverse = a * a_inverse;
         let epsilon = 0.000000000000001;
@@ -0,0 +1,229 @@
+use crate::matrix::Mat;
+
+/// damping factor of the first iteration
+const INITIAL_DAMPING: f64 = 1e-3;
+/// damping is multiplied or divided by this factor when a step fails or succeeds
+const DAMPING_FACTOR: f64 = 10.0;
+/// give up when the damping grows beyond this, i.e. no step decreases the residuals any more
+const MAX_DAMPING: f64 = 1e16;
+
+/// Parameters which minimize the sum of squared residuals
+#[derive(Debug, Clone)]
+pub struct Minimum {
+    /// parameters found
+    pub x: Vec<f64>,
+    /// residuals at x
+    pub residuals: Vec<f64>,
+    /// ||residuals||, which is not 0 if residuals cannot be 0 at the same time
+    pub residual_norm: f64,
+    /// number of iterations taken to converge
+    pub iterations: usize,
+}
+
+/// Reason why the minimum is not found
+#[derive(Debug, Clone)]
+pub enum MinimizeError {
+    /// residuals are not finite at the initial guess
+    NotFinite,
+    /// tolerance is not reached within max iterations
+    /// - best - parameters which have the smallest residuals so far
+    NotConverged { best: Minimum },
+}
+
+/// Jacobian J(x) of residuals r(x) approximated by forward differences, whose element (i, j) is dr_i/dx_j
+///
+/// each x_j is moved by sqrt(machine epsilon) relative to its size, which balances truncation and rounding errors
+pub fn forward_differences<F>(residuals: &F, x: &[f64]) -> Mat
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let r = residuals(x);
+    let mut jacobian = Mat::zeros(r.len(), x.len());
+    let mut x_h = x.to_vec();
+    for j in 0..x.len() {
+        let h = f64::EPSILON.sqrt() * x[j].abs().max(1.0);
+        x_h[j] = x[j] + h;
+        let r_h = residuals(&x_h);
+        for i in 0..r.len() {
+            jacobian[i][j] = (r_h[i] - r[i]) / h;
+        }
+        x_h[j] = x[j];
+    }
+    jacobian
+}
+
+/// Minimize ||r(x)||^2 by Levenberg-Marquardt method with a jacobian of forward differences
+///
+/// - residuals - r(x), whose length must not change with x
+/// - initial - initial guess of x
+/// - tolerance - converged when a step of x or ||r(x)|| is smaller than this
+/// - max_iterations - give up after this number of iterations
+pub fn levenberg_marquardt<F>(
+    residuals: F,
+    initial: &[f64],
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<Minimum, MinimizeError>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+{
+    let jacobian = |x: &[f64]| forward_differences(&residuals, x);
+    levenberg_marquardt_with_jacobian(&residuals, jacobian, initial, tolerance, max_iterations)
+}
+
+/// Minimize ||r(x)||^2 by Levenberg-Marquardt method
+///
+/// each step solves the damped problem min ||J dx + r||^2 + damping ||s dx||^2 by QR factorization,
+/// where s is the largest column norm of J,
+/// hence it works even if J is rank deficient, i.e. some parameters do not affect residuals
+/// - residuals - r(x), whose length must not change with x
+/// - jacobian - J(x) whose element (i, j) is dr_i/dx_j
+/// - initial - initial guess of x
+/// - tolerance - converged when a step of x or ||r(x)|| is smaller than this
+/// - max_iterations - give up after this number of iterations
+pub fn levenberg_marquardt_with_jacobian<F, J>(
+    residuals: F,
+    jacobian: J,
+    initial: &[f64],
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<Minimum, MinimizeError>
+where
+    F: Fn(&[f64]) -> Vec<f64>,
+    J: Fn(&[f64]) -> Mat,
+{
+    let n = initial.len();
+    let norm = |v: &[f64]| v.iter().map(|a| a * a).sum::<f64>().sqrt();
+    let r = residuals(initial);
+    let m = r.len();
+    if r.iter().any(|a| !a.is_finite()) {
+        return Err(MinimizeError::NotFinite);
+    }
+    let mut best = Minimum {
+        x: initial.to_vec(),
+        residual_norm: norm(&r),
+        residuals: r,
+        iterations: 0,
+    };
+    let mut damping = INITIAL_DAMPING;
+    for iterations in 1..=max_iterations {
+        best.iterations = iterations;
+        if best.residual_norm <= tolerance {
+            return Ok(best);
+        }
+        let j = jacobian(&best.x);
+        assert_eq!(
+            (j.count_rows, j.count_columns),
+            (m, n),
+            "Jacobian must be (number of residuals) by (number of parameters)"
+        );
+        // scale damping by the largest column norm so that it does not depend on the scale of residuals.
+        // the same scale for all parameters keeps parameters which hardly affect residuals near their initial values
+        let scale = (0..n)
+            .map(|k| norm(&(0..m).map(|i| j[i][k]).collect::<Vec<f64>>()))
+            .fold(0.0, f64::max);
+        let scale = if scale > 0.0 { scale } else { 1.0 };
+        loop {
+            // | J                   | dx = | -r |
+            // | sqrt(damping) * s I |      |  0 |
+            let mut a = Mat::zeros(m + n, n);
+            let mut b = Mat::zeros(m + n, 1);
+            for i in 0..m {
+                a[i].copy_from_slice(&j[i]);
+                b[i][0] = -best.residuals[i];
+            }
+            for k in 0..n {
+                a[m + k][k] = damping.sqrt() * scale;
+            }
+            let dx = a.least_squares(&b).solution;
+            let x = (0..n).map(|k| best.x[k] + dx[k][0]).collect::<Vec<f64>>();
+            let r = residuals(&x);
+            let residual_norm = norm(&r);
+            let step = norm(&(0..n).map(|k| dx[k][0]).collect::<Vec<f64>>());
+            if residual_norm.is_finite() && residual_norm < best.residual_norm {
+                best = Minimum {
+                    x,
+                    residuals: r,
+                    residual_norm,
+                    iterations,
+                };
+                damping /= DAMPING_FACTOR;
+                if step <= tolerance {
+                    return Ok(best);
+                }
+                break;
+            }
+            damping *= DAMPING_FACTOR;
+            // no step decreases residuals, which means the minimum is reached
+            if step <= tolerance || damping > MAX_DAMPING {
+                return Ok(best);
+            }
+        }
+    }
+    Err(MinimizeError::NotConverged { best })
+}
+
+#[cfg(test)]
+mod test_optimize {
+    use crate::{
+        matrix::Mat,
+        optimize::{forward_differences, levenberg_marquardt, levenberg_marquardt_with_jacobian},
+    };
+
+    #[test]
+    fn test_levenberg_marquardt() {
+        // rosenbrock function (1 - x)^2 + 100 (y - x^2)^2
+        let rosenbrock = |x: &[f64]| vec![1.0 - x[0], 10.0 * (x[1] - x[0] * x[0])];
+        let minimum =
+            levenberg_marquardt(rosenbrock, &[-1.2, 1.0], 1e-10, 100).expect("Not converged");
+        assert!((minimum.x[0] - 1.0).abs() < 1e-8 && (minimum.x[1] - 1.0).abs() < 1e-8);
+        // fit y = a exp(b t) to points which do not lie on the curve exactly
+        let t = [0.0, 1.0, 2.0, 3.0];
+        let y = [2.1, 2.9, 4.6, 6.7];
+        let residuals = |x: &[f64]| {
+            (0..4)
+                .map(|i| x[0] * (x[1] * t[i]).exp() - y[i])
+                .collect::<Vec<f64>>()
+        };
+        let jacobian = |x: &[f64]| {
+            Mat::from_rows(
+                (0..4)
+                    .map(|i| {
+                        let e = (x[1] * t[i]).exp();
+                        vec![e, x[0] * t[i] * e]
+                    })
+                    .collect(),
+            )
+        };
+        let minimum =
+            levenberg_marquardt_with_jacobian(residuals, jacobian, &[1.0, 0.0], 1e-12, 100)
+                .expect("Not converged");
+        let numeric =
+            levenberg_marquardt(residuals, &[1.0, 0.0], 1e-12, 100).expect("Not converged");
+        assert!((minimum.x[0] - numeric.x[0]).abs() < 1e-6);
+        assert!((minimum.x[1] - numeric.x[1]).abs() < 1e-6);
+        assert!(minimum.residual_norm > 0.0);
+        // forward differences agree with the exact jacobian
+        let numeric_jacobian = forward_differences(&residuals, &minimum.x);
+        let j = jacobian(&minimum.x);
+        for i in 0..4 {
+            for k in 0..2 {
+                assert!((numeric_jacobian[i][k] - j[i][k]).abs() < 1e-6 * j[i][k].abs().max(1.0));
+            }
+        }
+        // the gradient of ||r||^2 is 0 at the minimum
+        for k in 0..2 {
+            let gradient = (0..4).map(|i| j[i][k] * minimum.residuals[i]).sum::<f64>();
+            assert!(gradient.abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_levenberg_marquardt_underdetermined() {
+        // the point on the circle of radius 2, where infinitely many solutions exist
+        let residuals = |x: &[f64]| vec![x[0] * x[0] + x[1] * x[1] - 4.0];
+        let minimum =
+            levenberg_marquardt(residuals, &[1.0, 0.5], 1e-12, 100).expect("Not converged");
+        assert!(minimum.residual_norm < 1e-12);
+    }
+}
@@ -0,0 +1,225 @@
+use std::ops;
+
+use crate::{binomial::binomial_row, roots::brent};
+
+/// give up polishing a root after this number of iterations
+const MAX_ITERATIONS: usize = 100;
+
+/// Polynomial in the power basis
+///
+///  p(x) = a0 + a1 x + a2 x^2 + ... + an x^n
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    /// coefficients[k] is the coefficient of x^k
+    pub coefficients: Vec<f64>,
+}
+
+impl Polynomial {
+    /// Highest power whose coefficient is not 0, which is 0 for constants
+    pub fn degree(&self) -> usize {
+        self.coefficients
+            .iter()
+            .rposition(|a| *a != 0.0)
+            .unwrap_or(0)
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(k, a)| k as f64 * a)
+                .collect(),
+        )
+    }
+
+    /// Evaluate p(x) by Horner's method
+    pub fn eval(&self, x: f64) -> f64 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |sum, a| sum * x + a)
+    }
+
+    /// Polynomial of the bernstein form in [0, 1]
+    ///
+    ///  p(t) = Sigma[ nCk * (1-t)^(n-k) * t^k * bk ]
+    /// - coefficients - b0, b1, ..., bn such as x or y of bezier control points
+    pub fn from_bernstein(coefficients: &[f64]) -> Polynomial {
+        if coefficients.is_empty() {
+            return Polynomial::new(vec![0.0]);
+        }
+        let n = coefficients.len() - 1;
+        let binomials_n = binomial_row(n);
+        Polynomial::new(
+            (0..=n)
+                .map(|k| {
+                    let binomials_k = binomial_row(k);
+                    let sum = (0..=k)
+                        .map(|i| {
+                            let sign = if (k - i) % 2 == 0 { 1.0 } else { -1.0 };
+                            sign * binomials_k[i] * coefficients[i]
+                        })
+                        .sum::<f64>();
+                    binomials_n[k] * sum
+                })
+                .collect(),
+        )
+    }
+
+    pub fn new(coefficients: Vec<f64>) -> Polynomial {
+        if coefficients.is_empty() {
+            Polynomial {
+                coefficients: vec![0.0],
+            }
+        } else {
+            Polynomial { coefficients }
+        }
+    }
+
+    /// All real roots in [from, to] in ascending order
+    ///
+    /// roots of the derivative split the interval into monotonic pieces, and each piece which
+    /// changes its sign has exactly one root, which is polished by Brent's method.
+    /// roots which touch 0 without changing the sign (double roots) are found at the roots of the derivative.
+    /// roots closer than the tolerance are regarded as one root, and a polynomial which is constantly 0 has no roots
+    /// - tolerance - error of x allowed
+    pub fn roots(&self, from: f64, to: f64, tolerance: f64) -> Vec<f64> {
+        let degree = self.degree();
+        if degree == 0 || from > to {
+            return Vec::new();
+        }
+        if degree == 1 {
+            let x = -self.coefficients[0] / self.coefficients[1];
+            return if from <= x && x <= to {
+                vec![x]
+            } else {
+                Vec::new()
+            };
+        }
+        let mut breakpoints = vec![from];
+        breakpoints.extend(self.derivative().roots(from, to, tolerance));
+        breakpoints.push(to);
+        // each monotonic piece has one root at most, which is on its edge if an edge touches 0
+        let touches = breakpoints
+            .iter()
+            .map(|x| self.touches_zero(*x, tolerance))
+            .collect::<Vec<bool>>();
+        let mut roots = Vec::new();
+        for i in 0..breakpoints.len() {
+            if touches[i] {
+                roots.push(breakpoints[i]);
+            }
+        }
+        for i in 1..breakpoints.len() {
+            let (a, b) = (breakpoints[i - 1], breakpoints[i]);
+            if !touches[i - 1] && !touches[i] && self.eval(a) * self.eval(b) < 0.0 {
+                if let Ok(root) = brent(|x| self.eval(x), a, b, tolerance, MAX_ITERATIONS) {
+                    roots.push(root.x);
+                }
+            }
+        }
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut unique_roots: Vec<f64> = Vec::new();
+        for root in roots {
+            match unique_roots.last() {
+                Some(last) if root - last <= tolerance => (),
+                _ => unique_roots.push(root),
+            }
+        }
+        unique_roots
+    }
+
+    /// Coefficients of the bernstein form in [0, 1] of the same degree as the coefficients
+    pub fn to_bernstein(&self) -> Vec<f64> {
+        let n = self.coefficients.len() - 1;
+        let binomials_n = binomial_row(n);
+        (0..=n)
+            .map(|i| {
+                let binomials_i = binomial_row(i);
+                (0..=i)
+                    .map(|k| binomials_i[k] / binomials_n[k] * self.coefficients[k])
+                    .sum::<f64>()
+            })
+            .collect()
+    }
+
+    /// true if p(x) is 0 within rounding errors, or within the change of p over the tolerance
+    fn touches_zero(&self, x: f64, tolerance: f64) -> bool {
+        let value = self.eval(x);
+        let rounding_error = 4.0
+            * self.coefficients.len() as f64
+            * f64::EPSILON
+            * Polynomial::new(self.coefficients.iter().map(|a| a.abs()).collect()).eval(x.abs());
+        let change = (self.eval(x + tolerance) - value)
+            .abs()
+            .max((self.eval(x - tolerance) - value).abs());
+        value.abs() <= rounding_error + change
+    }
+}
+
+impl_op_ex!(+|p1: &Polynomial, p2: &Polynomial| -> Polynomial {
+    let n = p1.coefficients.len().max(p2.coefficients.len());
+    Polynomial::new(
+        (0..n)
+            .map(|k| p1.coefficients.get(k).unwrap_or(&0.0) + p2.coefficients.get(k).unwrap_or(&0.0))
+            .collect(),
+    )
+});
+
+impl_op_ex!(-|p1: &Polynomial, p2: &Polynomial| -> Polynomial { p1 + p2 * -1.0 });
+
+impl_op_ex!(*|p1: &Polynomial, p2: &Polynomial| -> Polynomial {
+    let mut coefficients = vec![0.0; p1.coefficients.len() + p2.coefficients.len() - 1];
+    for (i, a) in p1.coefficients.iter().enumerate() {
+        for (j, b) in p2.coefficients.iter().enumerate() {
+            coefficients[i + j] += a * b;
+        }
+    }
+    Polynomial::new(coefficients)
+});
+
+impl_op_ex!(*|p: &Polynomial, scalar: f64| -> Polynomial {
+    Polynomial::new(p.coefficients.iter().map(|a| a * scalar).collect())
+});
+
+#[cfg(test)]
+mod test_polynomial {
+    use crate::polynomial::Polynomial;
+
+    #[test]
+    fn test_bernstein() {
+        // (1-t)^2 * 1 + 2(1-t)t * 3 + t^2 * 2 = 1 + 4t - 3t^2
+        let p = Polynomial::from_bernstein(&[1.0, 3.0, 2.0]);
+        assert_eq!(p.coefficients, vec![1.0, 4.0, -3.0]);
+        assert_eq!(p.eval(0.5), 2.25);
+        assert_eq!(p.derivative().coefficients, vec![4.0, -6.0]);
+        let bernstein = p.to_bernstein();
+        for (b, expected) in bernstein.iter().zip([1.0, 3.0, 2.0].iter()) {
+            assert!((b - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_roots() {
+        let x = Polynomial::new(vec![0.0, 1.0]);
+        let factor = |root: f64| &x - Polynomial::new(vec![root]);
+        // (x - 0.2)(x - 0.5)(x - 0.9)
+        let p = factor(0.2) * factor(0.5) * factor(0.9);
+        let roots = p.roots(0.0, 1.0, 1e-12);
+        assert_eq!(roots.len(), 3);
+        for (root, expected) in roots.iter().zip([0.2, 0.5, 0.9].iter()) {
+            assert!((root - expected).abs() < 1e-12);
+        }
+        assert_eq!(p.roots(0.3, 0.8, 1e-12).len(), 1);
+        // double root touches 0 without changing the sign
+        let p = factor(0.3) * factor(0.3) * factor(0.7);
+        let roots = p.roots(0.0, 1.0, 1e-9);
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - 0.3).abs() < 1e-6 && (roots[1] - 0.7).abs() < 1e-9);
+        // no real roots
+        let p = &x * &x + Polynomial::new(vec![1.0]);
+        assert!(p.roots(-10.0, 10.0, 1e-12).is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::matrix::Mat;
+
+/// QR factorization with column pivoting such that A P = Q R
+///
+/// - Q - m by m orthogonal matrix, kept as a product of householder reflections
+/// - R - m by n upper triangular matrix whose diagonal elements decrease in magnitude
+/// - P - permutation matrix which swaps columns of A
+pub struct Qr {
+    /// R on and above the diagonal and householder vectors below the diagonal, stored in row-major order
+    qr: Vec<f64>,
+    /// householder reflection k is H = I - beta[k] v v^T where v[k] = 1
+    beta: Vec<f64>,
+    /// permutation[k] is the column of A which is moved to the k-th column
+    pub permutation: Vec<usize>,
+    /// numerical rank of A
+    pub rank: usize,
+    count_rows: usize,
+    count_columns: usize,
+}
+
+/// Solution of the least squares problem which minimizes ||A x - b||
+pub struct LeastSquares {
+    /// n by k matrix whose columns are solutions for each column of b
+    pub solution: Mat,
+    /// m by k matrix b - A x
+    pub residuals: Mat,
+    /// ||b - A x|| for each column of b
+    pub residual_norms: Vec<f64>,
+    /// numerical rank of A. if it is less than n, the solution is not unique
+    pub rank: usize,
+}
+
+impl Qr {
+    /// Number of diagonal elements of R larger than the tolerance times the largest one
+    ///
+    /// use this instead of `rank` if A has errors larger than rounding errors, such as numerical derivatives
+    pub fn rank_with_tolerance(&self, tolerance: f64) -> usize {
+        let n = self.count_columns;
+        if n == 0 || self.count_rows == 0 {
+            return 0;
+        }
+        let largest = self.qr[0].abs();
+        (0..self.count_rows.min(n))
+            .take_while(|&k| self.qr[k * n + k].abs() > tolerance * largest)
+            .count()
+    }
+
+    /// Solve A X = B in the least squares sense
+    ///
+    /// If A is rank deficient, returns the basic solution whose elements corresponding to
+    /// dependent columns are 0.
+    pub fn solve(&self, b: &Mat) -> Mat {
+        let (m, n) = (self.count_rows, self.count_columns);
+        assert_eq!(b.count_rows, m, "Rows of b must be the same as A");
+        let mut x = Mat::zeros(n, b.count_columns);
+        for j in 0..b.count_columns {
+            // c = Q^T b
+            let mut c = (0..m).map(|i| b[i][j]).collect::<Vec<f64>>();
+            for k in 0..self.beta.len() {
+                let w = c[k] + (k + 1..m).map(|i| self.qr[i * n + k] * c[i]).sum::<f64>();
+                let w = self.beta[k] * w;
+                for (i, c) in c.iter_mut().enumerate().skip(k) {
+                    *c -= w * if i == k { 1.0 } else { self.qr[i * n + k] };
+                }
+            }
+            // backward substitution R11 z = c
+            let mut z = vec![0.0; self.rank];
+            for i in (0..self.rank).rev() {
+                let sum = (i + 1..self.rank)
+                    .map(|k| self.qr[i * n + k] * z[k])
+                    .sum::<f64>();
+                z[i] = (c[i] - sum) / self.qr[i * n + i];
+            }
+            for k in 0..self.rank {
+                x[self.permutation[k]][j] = z[k];
+            }
+        }
+        x
+    }
+}
+
+impl Mat {
+    /// Solve A X = B in the least squares sense for m by n matrix A where m >= n
+    ///
+    /// over-determined systems get the solution which minimizes ||A x - b|| with its residuals
+    pub fn least_squares(&self, b: &Mat) -> LeastSquares {
+        let qr = self.qr();
+        let solution = qr.solve(b);
+        let residuals = b - self * &solution;
+        let residual_norms = (0..residuals.count_columns)
+            .map(|j| {
+                (0..residuals.count_rows)
+                    .map(|i| residuals[i][j].powi(2))
+                    .sum::<f64>()
+                    .sqrt()
+            })
+            .collect();
+        LeastSquares {
+            solution,
+            residuals,
+            residual_norms,
+            rank: qr.rank,
+        }
+    }
+
+    /// Factorize m by n matrix into A P = Q R by householder reflections with column pivoting
+    pub fn qr(&self) -> Qr {
+        let (m, n) = (self.count_rows, self.count_columns);
+        let mut qr = self.as_slice().to_vec();
+        let mut permutation = (0..n).collect::<Vec<usize>>();
+        let mut beta = Vec::new();
+        for k in 0..m.min(n) {
+            // move the column which has the largest norm to the k-th column
+            let column_norm =
+                |qr: &Vec<f64>, j: usize| (k..m).map(|i| qr[i * n + j].powi(2)).sum::<f64>();
+            let mut max_column = k;
+            for j in k + 1..n {
+                if column_norm(&qr, j) > column_norm(&qr, max_column) {
+                    max_column = j;
+                }
+            }
+            if max_column != k {
+                for i in 0..m {
+                    qr.swap(i * n + k, i * n + max_column);
+                }
+                permutation.swap(k, max_column);
+            }
+            // householder reflection which maps the column to (alpha, 0, ..., 0)
+            let norm = column_norm(&qr, k).sqrt();
+            if norm == 0.0 {
+                beta.push(0.0);
+                continue;
+            }
+            let x0 = qr[k * n + k];
+            let alpha = if x0 > 0.0 { -norm } else { norm };
+            let v0 = x0 - alpha;
+            for i in k + 1..m {
+                qr[i * n + k] /= v0;
+            }
+            let v_dot_v = 1.0 + (k + 1..m).map(|i| qr[i * n + k].powi(2)).sum::<f64>();
+            let b = 2.0 / v_dot_v;
+            qr[k * n + k] = alpha;
+            // apply the reflection to the rest of columns
+            for j in k + 1..n {
+                let w = qr[k * n + j]
+                    + (k + 1..m)
+                        .map(|i| qr[i * n + k] * qr[i * n + j])
+                        .sum::<f64>();
+                let w = b * w;
+                qr[k * n + j] -= w;
+                for i in k + 1..m {
+                    qr[i * n + j] -= w * qr[i * n + k];
+                }
+            }
+            beta.push(b);
+        }
+        // diagonal elements smaller than the tolerance are regarded as 0
+        let tolerance = m.max(n) as f64 * f64::EPSILON * if n > 0 { qr[0].abs() } else { 0.0 };
+        let rank = (0..m.min(n))
+            .take_while(|&k| qr[k * n + k].abs() > tolerance)
+            .count();
+        Qr {
+            qr,
+            beta,
+            permutation,
+            rank,
+            count_rows: m,
+            count_columns: n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_qr {
+    use crate::matrix::Mat;
+
+    #[test]
+    fn test_least_squares() {
+        // fit y = a + b x to (0, 1), (1, 3), (2, 5), (3, 6)
+        let a = Mat::from_rows(vec![
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+            vec![1.0, 2.0],
+            vec![1.0, 3.0],
+        ]);
+        let b = Mat::from_rows(vec![vec![1.0], vec![3.0], vec![5.0], vec![6.0]]);
+        let least_squares = a.least_squares(&b);
+        assert_eq!(least_squares.rank, 2);
+        assert!((least_squares.solution[0][0] - 1.2).abs() < 1e-12);
+        assert!((least_squares.solution[1][0] - 1.7).abs() < 1e-12);
+        // residuals are orthogonal to the columns of A
+        let a_t_r = a.transpose() * &least_squares.residuals;
+        assert!(a_t_r[0][0].abs() < 1e-12 && a_t_r[1][0].abs() < 1e-12);
+        assert!((least_squares.residual_norms[0] - 0.3f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_least_squares_rank_deficient() {
+        // the second column is twice the first one
+        let a = Mat::from_rows(vec![vec![1.0, 2.0], vec![1.0, 2.0], vec![1.0, 2.0]]);
+        let b = Mat::from_rows(vec![vec![2.0], vec![4.0], vec![6.0]]);
+        let least_squares = a.least_squares(&b);
+        assert_eq!(least_squares.rank, 1);
+        let x = &least_squares.solution;
+        assert!((x[0][0] + 2.0 * x[1][0] - 4.0).abs() < 1e-12);
+        assert!((least_squares.residual_norms[0] - 8.0f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_rank_with_tolerance() {
+        // errors of the second column are regarded as 0 with the tolerance
+        let a = Mat::from_rows(vec![vec![1.0, 2.0], vec![1.0, 2.0 + 1e-9], vec![1.0, 2.0]]);
+        assert_eq!(a.qr().rank, 2);
+        assert_eq!(a.qr().rank_with_tolerance(1e-6), 1);
+    }
+}
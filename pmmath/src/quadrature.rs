@@ -0,0 +1,133 @@
+use crate::roots::{newton_bracketed, RootError};
+
+/// nodes of 8-point gauss-legendre quadrature in [-1, 1]
+const NODES: [f64; 8] = [
+    -0.9602898564975363,
+    -0.7966664774136267,
+    -0.525532409916329,
+    -0.1834346424956498,
+    0.1834346424956498,
+    0.525532409916329,
+    0.7966664774136267,
+    0.9602898564975363,
+];
+/// weights of 8-point gauss-legendre quadrature
+const WEIGHTS: [f64; 8] = [
+    0.1012285362903763,
+    0.2223810344533745,
+    0.3137066458778873,
+    0.362683783378362,
+    0.362683783378362,
+    0.3137066458778873,
+    0.2223810344533745,
+    0.1012285362903763,
+];
+/// intervals are not divided more than this number of times
+const MAX_DEPTH: usize = 20;
+/// give up inverting arc length after this number of iterations
+const MAX_ITERATIONS: usize = 100;
+
+/// Integrate f from a to b by 8-point gauss-legendre quadrature
+///
+/// exact for polynomials of degree 15 or less
+pub fn gauss_legendre<F>(f: &F, a: f64, b: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let half_width = (b - a) / 2.0;
+    let middle = (a + b) / 2.0;
+    half_width
+        * NODES
+            .iter()
+            .zip(WEIGHTS.iter())
+            .map(|(x, w)| w * f(middle + half_width * x))
+            .sum::<f64>()
+}
+
+/// Integrate f from a to b by adaptive gauss-legendre quadrature
+///
+/// intervals are halved until the sum of halves agrees with the whole within the tolerance
+pub fn integrate<F>(f: &F, a: f64, b: f64, tolerance: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    integrate_adaptive(f, a, b, gauss_legendre(f, a, b), tolerance, MAX_DEPTH)
+}
+
+fn integrate_adaptive<F>(f: &F, a: f64, b: f64, whole: f64, tolerance: f64, depth: usize) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let middle = (a + b) / 2.0;
+    let left = gauss_legendre(f, a, middle);
+    let right = gauss_legendre(f, middle, b);
+    if depth == 0 || (left + right - whole).abs() <= tolerance {
+        left + right
+    } else {
+        integrate_adaptive(f, a, middle, left, tolerance / 2.0, depth - 1)
+            + integrate_adaptive(f, middle, b, right, tolerance / 2.0, depth - 1)
+    }
+}
+
+/// Find t such that the length of a parametric curve from a to t is equal to length
+///
+/// - speed - |dC/dt| of the curve C(t)
+/// - a, b - the curve is defined for a <= t <= b
+/// - length - arc length from a
+/// - tolerance - error of the length allowed
+pub fn inverse_arc_length<F>(
+    speed: &F,
+    a: f64,
+    b: f64,
+    length: f64,
+    tolerance: f64,
+) -> Result<f64, RootError>
+where
+    F: Fn(f64) -> f64,
+{
+    if length <= 0.0 {
+        return Ok(a);
+    }
+    let arc_length = |t: f64| integrate(speed, a, t, tolerance / 10.0) - length;
+    // newton's method converges in t, thus tolerance of length is converted by the maximum speed
+    let max_speed = (0..=16)
+        .map(|i| speed(a + (b - a) * i as f64 / 16.0))
+        .fold(f64::EPSILON, f64::max);
+    newton_bracketed(
+        arc_length,
+        speed,
+        a,
+        b,
+        tolerance / max_speed,
+        MAX_ITERATIONS,
+    )
+    .map(|root| root.x)
+}
+
+#[cfg(test)]
+mod test_quadrature {
+    use crate::quadrature::{gauss_legendre, integrate, inverse_arc_length};
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_integrate() {
+        // exact for polynomials
+        let integral = gauss_legendre(&|x: f64| x.powi(7) - 3.0 * x.powi(2), 0.0, 2.0);
+        assert!((integral - 24.0).abs() < 1e-12);
+        let integral = integrate(&|x: f64| x.sin(), 0.0, PI, 1e-12);
+        assert!((integral - 2.0).abs() < 1e-12);
+        // a sharp peak needs adaptive intervals
+        let integral = integrate(&|x: f64| 1.0 / (1e-4 + x * x), -1.0, 1.0, 1e-9);
+        assert!((integral - 2.0 * 100.0 * (100.0f64).atan()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_inverse_arc_length() {
+        // parabola (t, t^2)
+        let speed = |t: f64| (1.0 + 4.0 * t * t).sqrt();
+        let length = integrate(&speed, 0.0, 0.7, 1e-12);
+        let t = inverse_arc_length(&speed, 0.0, 2.0, length, 1e-10).expect("Failed to invert");
+        assert!((t - 0.7).abs() < 1e-9);
+        assert!(inverse_arc_length(&speed, 0.0, 2.0, 100.0, 1e-10).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+/// Root found by an iterative method
+#[derive(Debug, Clone, Copy)]
+pub struct Root {
+    /// x such that f(x) = 0 within the tolerance
+    pub x: f64,
+    /// number of iterations taken to converge
+    pub iterations: usize,
+}
+
+/// Reason why a root is not found
+#[derive(Debug, Clone, Copy)]
+pub enum RootError {
+    /// f(a) and f(b) have the same sign, hence a root is not guaranteed between a and b
+    NotBracketed,
+    /// tolerance is not reached within max iterations
+    /// - best - the last estimate of the root
+    NotConverged { best: f64 },
+}
+
+/// Find a root of f in [a, b] by Brent's method
+///
+/// combines bisection, secant, and inverse quadratic interpolation.
+/// it never diverges as long as f(a) and f(b) have opposite signs
+/// - tolerance - error of x allowed
+/// - max_iterations - give up after this number of iterations
+pub fn brent<F>(
+    f: F,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<Root, RootError>
+where
+    F: Fn(f64) -> f64,
+{
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa == 0.0 {
+        return Ok(Root {
+            x: a,
+            iterations: 0,
+        });
+    }
+    if fb == 0.0 {
+        return Ok(Root {
+            x: b,
+            iterations: 0,
+        });
+    }
+    if (fa > 0.0) == (fb > 0.0) {
+        return Err(RootError::NotBracketed);
+    }
+    // b is the best estimate, and c is the opposite side of the bracket
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (b - a, b - a);
+    for iterations in 1..=max_iterations {
+        if (fb > 0.0) == (fc > 0.0) {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        let tol = 2.0 * f64::EPSILON * b.abs() + 0.5 * tolerance;
+        let middle = 0.5 * (c - b);
+        if middle.abs() <= tol || fb == 0.0 {
+            return Ok(Root { x: b, iterations });
+        }
+        if e.abs() >= tol && fa.abs() > fb.abs() {
+            // try interpolation
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                // secant
+                (2.0 * middle * s, 1.0 - s)
+            } else {
+                // inverse quadratic interpolation
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2.0 * middle * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            } else {
+                p = -p;
+            }
+            if 2.0 * p < (3.0 * middle * q - (tol * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                // interpolation failed, use bisection
+                d = middle;
+                e = d;
+            }
+        } else {
+            // bounds decrease too slowly, use bisection
+            d = middle;
+            e = d;
+        }
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol {
+            d
+        } else {
+            tol.copysign(middle)
+        };
+        fb = f(b);
+    }
+    Err(RootError::NotConverged { best: b })
+}
+
+/// Find a root of f in [a, b] by Newton's method safeguarded by bisection
+///
+/// newton steps which jump out of the bracket or converge slowly are replaced by bisection,
+/// hence it never diverges as long as f(a) and f(b) have opposite signs
+/// - df - derivative of f
+/// - tolerance - error of x allowed
+/// - max_iterations - give up after this number of iterations
+pub fn newton_bracketed<F, D>(
+    f: F,
+    df: D,
+    a: f64,
+    b: f64,
+    tolerance: f64,
+    max_iterations: usize,
+) -> Result<Root, RootError>
+where
+    F: Fn(f64) -> f64,
+    D: Fn(f64) -> f64,
+{
+    let (fa, fb) = (f(a), f(b));
+    if fa == 0.0 {
+        return Ok(Root {
+            x: a,
+            iterations: 0,
+        });
+    }
+    if fb == 0.0 {
+        return Ok(Root {
+            x: b,
+            iterations: 0,
+        });
+    }
+    if (fa > 0.0) == (fb > 0.0) {
+        return Err(RootError::NotBracketed);
+    }
+    // f(low) < 0 < f(high)
+    let (mut low, mut high) = if fa < 0.0 { (a, b) } else { (b, a) };
+    let mut x = 0.5 * (a + b);
+    let mut dx_old = (b - a).abs();
+    let mut dx = dx_old;
+    let mut fx = f(x);
+    let mut dfx = df(x);
+    for iterations in 1..=max_iterations {
+        let jumps_out = ((x - high) * dfx - fx) * ((x - low) * dfx - fx) > 0.0;
+        let is_slow = (2.0 * fx).abs() > (dx_old * dfx).abs();
+        dx_old = dx;
+        if jumps_out || is_slow {
+            dx = 0.5 * (high - low);
+            x = low + dx;
+        } else {
+            dx = fx / dfx;
+            x -= dx;
+        }
+        if dx.abs() < tolerance {
+            return Ok(Root { x, iterations });
+        }
+        fx = f(x);
+        dfx = df(x);
+        if fx == 0.0 {
+            return Ok(Root { x, iterations });
+        }
+        if fx < 0.0 {
+            low = x;
+        } else {
+            high = x;
+        }
+    }
+    Err(RootError::NotConverged { best: x })
+}
+
+#[cfg(test)]
+mod test_roots {
+    use crate::roots::{brent, newton_bracketed, RootError};
+
+    #[test]
+    fn test_brent() {
+        let root = brent(|x| x.cos() - x, 0.0, 1.0, 1e-12, 100).expect("Failed to find root");
+        assert!((root.x - 0.7390851332151607).abs() < 1e-12);
+        assert!(matches!(
+            brent(|x| x * x + 1.0, -1.0, 1.0, 1e-12, 100),
+            Err(RootError::NotBracketed)
+        ));
+        assert!(matches!(
+            brent(|x| x.cos() - x, 0.0, 1.0, 1e-12, 2),
+            Err(RootError::NotConverged { .. })
+        ));
+    }
+
+    #[test]
+    fn test_newton_bracketed() {
+        let f = |x: f64| x.powi(3) - 2.0 * x - 5.0;
+        let df = |x: f64| 3.0 * x.powi(2) - 2.0;
+        let root = newton_bracketed(f, df, 2.0, 3.0, 1e-12, 100).expect("Failed to find root");
+        assert!((root.x - 2.0945514815423265).abs() < 1e-12);
+        // horizontal tangent at x = 0 does not make it diverge
+        let root = newton_bracketed(|x| x.powi(3) - 1.0, |x| 3.0 * x * x, -1.0, 2.0, 1e-12, 100)
+            .expect("Failed to find root");
+        assert!((root.x - 1.0).abs() < 1e-12);
+    }
+}
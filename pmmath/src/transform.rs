@@ -0,0 +1,186 @@
+use std::ops;
+
+use crate::matrix::NotInvertible;
+
+/// Affine transformation of 2D coordinates
+///
+///  | x' |   | a b c | | x |
+///  | y' | = | d e f | | y |
+///  | 1  |   | 0 0 1 | | 1 |
+///
+/// `t2 * t1` is the transformation which applies t1 first and then t2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2 {
+    /// upper two rows of the 3 by 3 matrix, (a b c) and (d e f)
+    pub rows: [[f64; 3]; 2],
+}
+
+impl Transform2 {
+    /// Transform a point
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let [[a, b, c], [d, e, f]] = self.rows;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+
+    /// Transform a direction, which is not affected by translation
+    pub fn apply_vector(&self, x: f64, y: f64) -> (f64, f64) {
+        let [[a, b, _], [d, e, _]] = self.rows;
+        (a * x + b * y, d * x + e * y)
+    }
+
+    /// Transformation which applies this first and then another
+    pub fn then(&self, another: &Transform2) -> Transform2 {
+        another * self
+    }
+
+    /// Ratio of areas after and before the transformation, which is negative if it mirrors
+    pub fn determinant(&self) -> f64 {
+        let [[a, b, _], [d, e, _]] = self.rows;
+        a * e - b * d
+    }
+
+    pub fn identity() -> Transform2 {
+        Transform2::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0)
+    }
+
+    /// Transformation which undoes this
+    pub fn inverse(&self) -> Result<Transform2, NotInvertible> {
+        let det = self.determinant();
+        if det == 0.0 {
+            return Err(NotInvertible);
+        }
+        let [[a, b, c], [d, e, f]] = self.rows;
+        Ok(Transform2::new(
+            e / det,
+            -b / det,
+            (b * f - c * e) / det,
+            -d / det,
+            a / det,
+            (c * d - a * f) / det,
+        ))
+    }
+
+    /// true if it keeps distances and angles, i.e. it consists of translation, rotation, and mirror
+    pub fn is_rigid(&self) -> bool {
+        let [[a, b, _], [d, e, _]] = self.rows;
+        let epsilon = 1e-12;
+        (a * a + d * d - 1.0).abs() < epsilon
+            && (b * b + e * e - 1.0).abs() < epsilon
+            && (a * b + d * e).abs() < epsilon
+    }
+
+    /// true if it keeps angles, i.e. it consists of translation, rotation, mirror, and uniform scaling
+    ///
+    /// circles stay circles only under these transformations
+    pub fn is_similarity(&self) -> bool {
+        let [[a, b, _], [d, e, _]] = self.rows;
+        let (column0, column1) = (a * a + d * d, b * b + e * e);
+        let epsilon = 1e-12 * column0.max(column1);
+        (column0 - column1).abs() <= epsilon && (a * b + d * e).abs() <= epsilon
+    }
+
+    /// Mirror by the line which passes through (x0, y0) and (x1, y1)
+    pub fn mirror(x0: f64, y0: f64, x1: f64, y1: f64) -> Transform2 {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let norm = (dx * dx + dy * dy).sqrt();
+        let (ux, uy) = (dx / norm, dy / norm);
+        let reflection = Transform2::new(
+            2.0 * ux * ux - 1.0,
+            2.0 * ux * uy,
+            0.0,
+            2.0 * ux * uy,
+            2.0 * uy * uy - 1.0,
+            0.0,
+        );
+        Transform2::translate(-x0, -y0)
+            .then(&reflection)
+            .then(&Transform2::translate(x0, y0))
+    }
+
+    /// | a b c |
+    /// | d e f |
+    pub fn new(a: f64, b: f64, c: f64, d: f64, e: f64, f: f64) -> Transform2 {
+        Transform2 {
+            rows: [[a, b, c], [d, e, f]],
+        }
+    }
+
+    /// Rotate counter clockwise around the origin
+    pub fn rotate(angle_degree: f64) -> Transform2 {
+        let theta = angle_degree.to_radians();
+        let (sin, cos) = theta.sin_cos();
+        Transform2::new(cos, -sin, 0.0, sin, cos, 0.0)
+    }
+
+    /// Rotate counter clockwise around (x, y)
+    pub fn rotate_around(angle_degree: f64, x: f64, y: f64) -> Transform2 {
+        Transform2::translate(-x, -y)
+            .then(&Transform2::rotate(angle_degree))
+            .then(&Transform2::translate(x, y))
+    }
+
+    /// Scale from the origin
+    pub fn scale(sx: f64, sy: f64) -> Transform2 {
+        Transform2::new(sx, 0.0, 0.0, 0.0, sy, 0.0)
+    }
+
+    /// Shear such that x' = x + kx y and y' = y + ky x
+    pub fn shear(kx: f64, ky: f64) -> Transform2 {
+        Transform2::new(1.0, kx, 0.0, ky, 1.0, 0.0)
+    }
+
+    pub fn translate(dx: f64, dy: f64) -> Transform2 {
+        Transform2::new(1.0, 0.0, dx, 0.0, 1.0, dy)
+    }
+}
+
+impl_op_ex!(*|t2: &Transform2, t1: &Transform2| -> Transform2 {
+    let [[a2, b2, c2], [d2, e2, f2]] = t2.rows;
+    let [[a1, b1, c1], [d1, e1, f1]] = t1.rows;
+    Transform2::new(
+        a2 * a1 + b2 * d1,
+        a2 * b1 + b2 * e1,
+        a2 * c1 + b2 * f1 + c2,
+        d2 * a1 + e2 * d1,
+        d2 * b1 + e2 * e1,
+        d2 * c1 + e2 * f1 + f2,
+    )
+});
+
+#[cfg(test)]
+mod test_transform {
+    use crate::transform::Transform2;
+
+    fn assert_near((x0, y0): (f64, f64), (x1, y1): (f64, f64)) {
+        assert!((x0 - x1).abs() < 1e-12 && (y0 - y1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_transform() {
+        assert_near(Transform2::rotate(90.0).apply(1.0, 0.0), (0.0, 1.0));
+        assert_near(
+            Transform2::rotate_around(180.0, 1.0, 1.0).apply(0.0, 0.0),
+            (2.0, 2.0),
+        );
+        // mirror by y = x + 1
+        let mirror = Transform2::mirror(0.0, 1.0, 1.0, 2.0);
+        assert_near(mirror.apply(1.0, 0.0), (-1.0, 2.0));
+        assert!(mirror.is_rigid() && mirror.determinant() < 0.0);
+        // compose
+        let t = Transform2::scale(2.0, 3.0).then(&Transform2::translate(1.0, -1.0));
+        assert_near(t.apply(1.0, 1.0), (3.0, 2.0));
+        assert_near(t.apply_vector(1.0, 1.0), (2.0, 3.0));
+        let shear = Transform2::shear(0.5, 0.0);
+        assert_near(shear.apply(0.0, 2.0), (1.0, 2.0));
+        // inverse
+        let t = shear.then(&t).then(&Transform2::rotate(30.0));
+        let identity = t * t.inverse().expect("Not invertible");
+        assert_near(identity.apply(5.0, -7.0), (5.0, -7.0));
+        assert!(Transform2::scale(0.0, 1.0).inverse().is_err());
+        // similarity
+        assert!(Transform2::scale(3.0, 3.0).then(&mirror).is_similarity());
+        assert!(!Transform2::scale(3.0, 3.0).is_rigid());
+        assert!(!Transform2::scale(2.0, 3.0).is_similarity());
+        assert!(!shear.is_similarity());
+    }
+}